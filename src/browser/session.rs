@@ -4,18 +4,28 @@ use chromiumoxide::{
     cdp::browser_protocol::{
         //emulation::SetGeolocationOverrideParams,
         network::CookieParam,
+        page::CaptureScreenshotFormat,
     },
+    page::ScreenshotParams,
 };
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 use tokio::{
     task::JoinHandle,
     time::{sleep, timeout},
 };
 use tokio_stream::StreamExt;
+use tracing::Instrument;
 
 pub use super::error::BrowserError;
 use super::extension;
+use crate::i18n::msg;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MyIP {
@@ -163,6 +173,8 @@ pub struct BrowserSession {
     pub browser: Browser,
     pub handle: JoinHandle<()>,
     pub timings: BrowserTimings,
+    /// Закрытие начато программой: завершение соединения не считается ошибкой
+    closing: Arc<AtomicBool>,
 }
 
 #[allow(dead_code)]
@@ -170,13 +182,30 @@ impl BrowserSession {
     pub async fn launch(bsc: &BrowserSessionConfig) -> Result<Self, BrowserError> {
         let timings = bsc.timings.clone();
         let (browser, mut handler) = Browser::launch(bsc.to_config()?).await?;
-        let handle = tokio::task::spawn(async move { while handler.next().await.is_some() {} });
+        let closing = Arc::new(AtomicBool::new(false));
+        let handle = tokio::task::spawn(
+            {
+                let closing = Arc::clone(&closing);
+                async move {
+                    while let Some(event) = handler.next().await {
+                        if let Err(e) = event {
+                            tracing::debug!(error = %e, "browser handler");
+                        }
+                    }
+                    if !closing.load(Ordering::Relaxed) {
+                        tracing::error!("{}", msg().browser_connection_lost);
+                    }
+                }
+            }
+            .in_current_span(),
+        );
         sleep(Duration::from_millis(timings.launch_sleep)).await;
 
         Ok(Self {
             browser,
             handle,
             timings,
+            closing,
         })
     }
 
@@ -185,11 +214,17 @@ impl BrowserSession {
         Self::launch(&config).await
     }
 
+    /// Соединение с браузером не потеряно
+    pub fn is_alive(&self) -> bool {
+        !self.handle.is_finished()
+    }
+
     pub async fn set_timings(&mut self, timings: BrowserTimings) {
         self.timings = timings;
     }
 
     pub async fn close(&mut self) {
+        self.closing.store(true, Ordering::Relaxed);
         if self.browser.close().await.is_err() {
             self.browser.kill().await;
         }
@@ -288,6 +323,29 @@ impl BrowserSession {
         Ok(page)
     }
 
+    /// Скриншот страницы в формате PNG. Если указан селектор — только элемента
+    pub async fn screenshot<'a>(
+        &self,
+        url: &str,
+        params: &PageParams<'a>,
+        selector: Option<&str>,
+    ) -> Result<Vec<u8>, BrowserError> {
+        let page = self.open_with_params(url, params).await?;
+        let screenshot = match selector {
+            Some(selector) => match page.find_element(selector).await {
+                Ok(el) => el.screenshot(CaptureScreenshotFormat::Png).await,
+                Err(e) => Err(e),
+            },
+            None => {
+                page.screenshot(ScreenshotParams::builder().full_page(true).build())
+                    .await
+            }
+        };
+        let _ = page.close().await;
+
+        Ok(screenshot?)
+    }
+
     pub async fn set_proxy(&self, proxy: &str) -> Result<(), BrowserError> {
         if let Err(e) = self
             .browser
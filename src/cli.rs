@@ -0,0 +1,144 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::config::{self, Sources};
+
+/// Отслеживание доступных слотов приёмки поставок Wildberries
+#[derive(Debug, Parser)]
+#[command(name = "wbsupplies", version)]
+pub struct Cli {
+    /// Путь к файлу конфигурации
+    #[arg(
+        short,
+        long,
+        global = true,
+        env = config::ENV_CONFIG_PATH,
+        default_value = config::CONFIG_PATH
+    )]
+    pub config: PathBuf,
+
+    /// Переопределение параметра конфигурации, например `-s browser.port=9000`.
+    /// Имеет приоритет над файлом и переменными окружения WBSUPPLIES_*
+    #[arg(
+        short = 's',
+        long = "set",
+        global = true,
+        value_name = "KEY=VALUE",
+        value_parser = parse_key_value
+    )]
+    pub overrides: Vec<(String, String)>,
+
+    /// Неинтерактивный режим (например, под systemd): вместо ожидания ввода
+    /// и открытия файла конфигурации программа завершается с ошибкой
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
+    /// Файл, в который при отслеживании записывается PID процесса.
+    /// Относительный путь отсчитывается от папки файла конфигурации
+    #[arg(long, global = true, value_name = "PATH")]
+    pub pidfile: Option<PathBuf>,
+
+    /// Команда. Без команды — отслеживание поставок
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Вход в личный кабинет в окне браузера
+    Login {
+        /// Название аккаунта из `[[accounts]]`. Если не указано — все аккаунты
+        #[arg(short, long)]
+        account: Option<String>,
+    },
+    /// Отслеживание поставок и отправка уведомлений
+    Watch,
+    /// Список поставок
+    List {
+        /// Статус поставок: not-planned, all или числовой ID статуса
+        #[arg(
+            long,
+            default_value = "not-planned",
+            allow_hyphen_values = true,
+            value_parser = parse_status
+        )]
+        status: i8,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Даты и стоимость приёмки поставки
+    Costs {
+        preorder_id: i64,
+        /// Период в днях. По умолчанию — tracking_supplies.days
+        #[arg(long)]
+        days: Option<u8>,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Выгрузка не запланированных поставок с датами приёмки
+    Export {
+        /// Период в днях. По умолчанию — tracking_supplies.days
+        #[arg(long)]
+        days: Option<u8>,
+        /// Файл для записи. Если не указан — стандартный вывод
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Работа с файлом конфигурации
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Проверка конфигурации и прав доступа к файлам с секретами
+    Check,
+}
+
+/// Аккаунт и формат вывода команд, запрашивающих данные личного кабинета
+#[derive(Debug, Args)]
+pub struct Output {
+    /// Название аккаунта из `[[accounts]]`. Если не указано — все аккаунты
+    /// (для `costs` — первый)
+    #[arg(short, long)]
+    pub account: Option<String>,
+
+    /// Формат вывода
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+impl Cli {
+    /// Источники конфигурации с учётом параметров командной строки
+    pub fn sources(&self) -> Sources {
+        Sources::new(&self.config, self.overrides.clone())
+    }
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+        .filter(|(k, _)| !k.is_empty())
+        .ok_or_else(|| format!("KEY=VALUE: {s}"))
+}
+
+/// Статус поставок: not-planned (-1), all (-2) или числовой ID
+fn parse_status(s: &str) -> Result<i8, String> {
+    match s {
+        "not-planned" => Ok(-1),
+        "all" => Ok(-2),
+        _ => s
+            .parse::<i8>()
+            .map_err(|_| format!("not-planned, all, <ID>: {s}")),
+    }
+}
@@ -0,0 +1,358 @@
+//! Команды командной строки, кроме отслеживания поставок
+
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{self, Write},
+    net::SocketAddr,
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    browser::{BrowserSession, BrowserSessionConfig},
+    cli::{Format, Output},
+    config::{self, Account, Config},
+    error::{Error, Result},
+    i18n::msg,
+    notify::short_date,
+    wbseller::{
+        self,
+        models::{Cost, Supply},
+    },
+};
+
+/// Поставка аккаунта в JSON: поля поставки, название аккаунта и даты приёмки
+#[derive(Serialize)]
+struct AccountSupply<'a> {
+    /// Не передаётся, если аккаунт один и не назван
+    #[serde(skip_serializing_if = "str::is_empty")]
+    account: &'a str,
+    #[serde(flatten)]
+    supply: &'a Supply,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    costs: Option<&'a [Cost]>,
+}
+
+/// Сколько ждать ответа на порту браузера при проверке, не запущен ли он
+const PORT_CHECK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Аккаунты, выбранные параметром `--account`
+fn select_accounts(cfg: &Config, name: Option<&str>) -> Result<Vec<Account>> {
+    let accounts = cfg.accounts();
+    let Some(name) = name else {
+        return Ok(accounts);
+    };
+    accounts
+        .into_iter()
+        .find(|a| a.name == name)
+        .map(|a| vec![a])
+        .ok_or_else(|| Error::UnknownAccount(name.to_string()))
+}
+
+/// Запуск браузера аккаунта. Если его порт занят, браузер с тем же профилем уже запущен
+/// (обычно отслеживанием `watch`), и второй экземпляр к нему не подключится
+async fn launch(bs_config: &BrowserSessionConfig) -> Result<BrowserSession> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], bs_config.port));
+    let in_use = bs_config.port != 0
+        && tokio::time::timeout(PORT_CHECK_TIMEOUT, tokio::net::TcpStream::connect(addr))
+            .await
+            .is_ok_and(|r| r.is_ok());
+    if in_use {
+        return Err(Error::BrowserInUse(bs_config.port));
+    }
+    Ok(BrowserSession::launch(bs_config).await?)
+}
+
+/// Клиент личного кабинета из сохранённой сессии браузера аккаунта
+async fn connect(cfg: &Config, account: &Account) -> Result<wbseller::Client> {
+    let bs_config = account.browser(&cfg.browser).to_browser_session_config();
+    let mut bs = launch(&bs_config).await?;
+    let client = wbseller::Client::from_browser_session(&bs).await;
+    bs.close().await;
+    client.map_err(Error::Login)
+}
+
+/// Вход в личный кабинет в окне браузера для выбранных аккаунтов
+pub async fn login(cfg: &Config, account: Option<&str>) -> Result<()> {
+    for account in select_accounts(cfg, account)? {
+        login_account(&account.browser(&cfg.browser), &account.name).await?;
+    }
+    Ok(())
+}
+
+async fn login_account(browser: &config::Browser, account: &str) -> Result<()> {
+    if !account.is_empty() {
+        eprintln!("{}: {account}", msg().login_account);
+    }
+
+    let mut browser_config = browser.clone();
+    browser_config.headless = config::Headless::False;
+    let bs_config = browser_config.to_browser_session_config();
+    let mut bs = launch(&bs_config).await?;
+    let page = bs.open("https://seller.wildberries.ru").await?;
+
+    // Показываем alert для входа
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let _ = page
+            .evaluate(format!(r#"alert("{}")"#, msg().login_alert))
+            .await;
+    });
+
+    eprintln!("{}", msg().login_waiting);
+    eprintln!("{}", msg().press_enter);
+    // Чтение stdin блокирует поток, поэтому выполняется вне рабочих потоков tokio
+    let _ = tokio::task::spawn_blocking(|| std::io::stdin().read_line(&mut String::new())).await;
+
+    // Проверяем успешность авторизации
+    let login = wbseller::Client::from_browser_session(&bs).await;
+    bs.close().await;
+    login.map_err(Error::Login)?;
+    eprintln!("{}", msg().login_success);
+    Ok(())
+}
+
+/// Список поставок с указанным статусом
+pub async fn list(cfg: &Config, status: i8, output: &Output) -> Result<()> {
+    let mut supplies = Vec::new();
+    for account in select_accounts(cfg, output.account.as_deref())? {
+        let client = connect(cfg, &account).await?;
+        let response = client.list_supplies(status).await?;
+        supplies.extend(
+            response
+                .result
+                .data
+                .into_iter()
+                .map(|s| (account.name.clone(), s)),
+        );
+    }
+
+    let stdout = &mut io::stdout().lock();
+    match output.format {
+        Format::Json => write_json(
+            stdout,
+            &supplies
+                .iter()
+                .map(|(account, supply)| AccountSupply {
+                    account,
+                    supply,
+                    costs: None,
+                })
+                .collect::<Vec<_>>(),
+        )?,
+        Format::Table => {
+            let rows = supplies
+                .iter()
+                .map(|(account, s)| {
+                    vec![
+                        account.clone(),
+                        optional(s.preorder_id),
+                        optional(s.supply_id),
+                        s.warehouse_name.clone(),
+                        s.status_name.clone(),
+                        short_date(&s.create_date).to_string(),
+                        s.supply_date
+                            .as_deref()
+                            .map(short_date)
+                            .unwrap_or("-")
+                            .to_string(),
+                    ]
+                })
+                .collect();
+            let (headers, rows) = account_column(
+                &[
+                    "ACCOUNT",
+                    "PREORDER_ID",
+                    "SUPPLY_ID",
+                    "WAREHOUSE",
+                    "STATUS",
+                    "CREATED",
+                    "SUPPLY_DATE",
+                ],
+                rows,
+            );
+            write_table(stdout, &headers, &rows)?;
+        }
+    }
+    Ok(())
+}
+
+/// Даты и стоимость приёмки поставки
+pub async fn costs(
+    cfg: &Config,
+    preorder_id: i64,
+    days: Option<u8>,
+    output: &Output,
+) -> Result<()> {
+    // Поставка принадлежит одному аккаунту, угадывать его нельзя
+    let mut accounts = select_accounts(cfg, output.account.as_deref())?;
+    if accounts.len() > 1 {
+        return Err(Error::AccountRequired);
+    }
+    let account = accounts.pop().unwrap_or_default();
+    let days = days.unwrap_or_else(|| cfg.tracking_supplies.to_tracking_options().days);
+
+    let client = connect(cfg, &account).await?;
+    let mut costs = client
+        .get_acceptance_costs(preorder_id, days)
+        .await?
+        .result
+        .costs;
+    costs.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let stdout = &mut io::stdout().lock();
+    match output.format {
+        Format::Json => write_json(stdout, &costs)?,
+        Format::Table => {
+            let rows = costs
+                .iter()
+                .map(|c| {
+                    vec![
+                        short_date(&c.date).to_string(),
+                        c.coefficient.to_string(),
+                        c.cost.to_string(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            write_table(stdout, &["DATE", "COEFFICIENT", "COST"], &rows)?;
+        }
+    }
+    Ok(())
+}
+
+/// Выгрузка не запланированных поставок с доступными датами приёмки
+pub async fn export(
+    cfg: &Config,
+    days: Option<u8>,
+    file: Option<&Path>,
+    output: &Output,
+) -> Result<()> {
+    let days = days.unwrap_or_else(|| cfg.tracking_supplies.to_tracking_options().days);
+
+    let mut supplies = Vec::new();
+    for account in select_accounts(cfg, output.account.as_deref())? {
+        let client = connect(cfg, &account).await?;
+        let list = client.not_planned_list_supplies().await?.result.data;
+        let mut costs = client.acceptance_costs_from_supplies(days, &list).await?;
+
+        for supply in list {
+            // Недоступные даты отмечены отрицательным коэффициентом
+            let mut available = supply
+                .preorder_id
+                .and_then(|id| costs.remove(&id))
+                .unwrap_or_default();
+            available.retain(|c| c.coefficient >= 0.);
+            available.sort_by(|a, b| a.date.cmp(&b.date));
+            supplies.push((account.name.clone(), supply, available));
+        }
+    }
+
+    let mut out: Box<dyn Write> = match file {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    };
+    match output.format {
+        Format::Json => write_json(
+            &mut out,
+            &supplies
+                .iter()
+                .map(|(account, supply, costs)| AccountSupply {
+                    account,
+                    supply,
+                    costs: Some(costs),
+                })
+                .collect::<Vec<_>>(),
+        )?,
+        Format::Table => {
+            let rows = supplies
+                .iter()
+                .flat_map(|(account, s, costs)| {
+                    costs.iter().map(move |c| {
+                        vec![
+                            account.clone(),
+                            optional(s.preorder_id),
+                            s.warehouse_name.clone(),
+                            short_date(&c.date).to_string(),
+                            c.coefficient.to_string(),
+                            c.cost.to_string(),
+                        ]
+                    })
+                })
+                .collect();
+            let (headers, rows) = account_column(
+                &[
+                    "ACCOUNT",
+                    "PREORDER_ID",
+                    "WAREHOUSE",
+                    "DATE",
+                    "COEFFICIENT",
+                    "COST",
+                ],
+                rows,
+            );
+            write_table(&mut out, &headers, &rows)?;
+        }
+    }
+    Ok(())
+}
+
+/// Проверка конфигурации без запуска отслеживания
+pub fn check_config() -> Result<()> {
+    let cfg = config::get()?;
+    for warning in cfg.secret_warnings(&config::sources().path) {
+        eprintln!("{}: {warning}", msg().warning);
+    }
+    println!("{}", msg().config_check_ok);
+    Ok(())
+}
+
+fn optional(value: Option<i64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value).map_err(io::Error::from)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Столбец аккаунта (первый) убирается, если аккаунт один и не назван
+fn account_column<'a>(
+    headers: &[&'a str],
+    mut rows: Vec<Vec<String>>,
+) -> (Vec<&'a str>, Vec<Vec<String>>) {
+    if rows.iter().any(|r| !r[0].is_empty()) {
+        return (headers.to_vec(), rows);
+    }
+    for row in &mut rows {
+        row.remove(0);
+    }
+    (headers[1..].to_vec(), rows)
+}
+
+/// Вывод таблицы с выравниванием столбцов
+fn write_table(out: &mut impl Write, headers: &[&str], rows: &[Vec<String>]) -> Result<()> {
+    let headers = headers.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+
+    let mut widths = headers
+        .iter()
+        .map(|h| h.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    for row in std::iter::once(&headers).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+//! Обновление файла конфигурации до текущей версии схемы
+
+use std::{ffi::OsString, fs, path::PathBuf};
+use toml_edit::{DocumentMut, Item, Table};
+
+use super::{Config, default_config_str, sources};
+use crate::{
+    error::{Error, Result},
+    i18n::{Language, msg},
+};
+
+/// Текущая версия схемы конфигурации. Увеличивается только при изменениях,
+/// которые требуют преобразования файла (переименование, перенос, смена типа значения).
+/// Новые ключи добавляются без смены версии
+pub const CONFIG_VERSION: i64 = 1;
+
+/// Изменения схемы: `MIGRATIONS[i]` переводит файл с версии `i` на `i + 1`
+const MIGRATIONS: [fn(&mut DocumentMut); CONFIG_VERSION as usize] = [
+    // 0 -> 1: файлы без поля version
+    |_| {},
+];
+
+/// Результат обновления файла конфигурации
+#[derive(Debug)]
+pub enum Migration {
+    /// Файл обновлён, путь к резервной копии
+    Updated(PathBuf),
+    /// Файл недоступен для записи. Недостающие ключи получают значения по умолчанию
+    /// только в памяти
+    NotWritable(Error),
+}
+
+/// Обновление файла конфигурации: преобразование устаревшей версии и добавление
+/// недостающих ключей. Комментарии и значения пользователя сохраняются,
+/// перед записью создаётся резервная копия.
+/// Возвращает `None`, если файл обновлять не нужно
+pub fn migrate() -> Result<Option<Migration>> {
+    let path = &sources().path;
+    let buf = match fs::read_to_string(path) {
+        Ok(buf) => buf,
+        // Конфигурация только из переопределений
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::ConfigRead(path.display().to_string(), e)),
+    };
+
+    let Some((version, updated)) = upgrade(&buf) else {
+        return Ok(None);
+    };
+
+    let mut backup = OsString::from(path.as_os_str());
+    backup.push(format!(".v{version}.bak"));
+    let backup = PathBuf::from(backup);
+
+    // Файл только для чтения (например, в образе контейнера) не мешает запуску
+    if let Err(e) = fs::copy(path, &backup) {
+        return Ok(Some(Migration::NotWritable(Error::ConfigWrite(
+            backup.display().to_string(),
+            e,
+        ))));
+    }
+    if let Err(e) = fs::write(path, updated) {
+        let _ = fs::remove_file(&backup);
+        return Ok(Some(Migration::NotWritable(Error::ConfigWrite(
+            path.display().to_string(),
+            e,
+        ))));
+    }
+
+    Ok(Some(Migration::Updated(backup)))
+}
+
+/// Преобразование текста файла к текущей версии с добавлением недостающих ключей.
+/// Возвращает прежнюю версию и новый текст, если он изменился
+fn upgrade(buf: &str) -> Option<(i64, String)> {
+    // Ошибки разбора сообщит загрузка конфигурации
+    let Ok(mut doc) = buf.parse::<DocumentMut>() else {
+        return None;
+    };
+
+    let version = doc
+        .get("version")
+        .and_then(Item::as_integer)
+        .unwrap_or(0)
+        .max(0);
+    // Файл более новой версии не изменяется, ошибку сообщит проверка конфигурации
+    if version > CONFIG_VERSION {
+        return None;
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut doc);
+    }
+
+    let language = doc
+        .get("language")
+        .and_then(Item::as_str)
+        .and_then(|s| toml::Value::from(s).try_into::<Language>().ok())
+        .unwrap_or_default();
+    let defaults = default_config_str(language)
+        .parse::<DocumentMut>()
+        .unwrap_or_else(|e| panic!("{}: {e}", msg().invalid_default_config));
+    // Значения добавляемых ключей совпадают с теми, что подставлялись при их отсутствии,
+    // поэтому обновление не меняет поведение программы
+    let values = toml::Table::try_from(Config {
+        language,
+        ..Default::default()
+    })
+    .unwrap_or_default();
+    add_missing(doc.as_table_mut(), defaults.as_table(), Some(&values));
+    set_version(&mut doc);
+
+    let updated = doc.to_string();
+    (updated != buf).then_some((version, updated))
+}
+
+/// Добавление отсутствующих ключей и таблиц с комментариями из конфигурации по умолчанию
+/// и значениями из `values`
+fn add_missing(table: &mut Table, defaults: &Table, values: Option<&toml::Table>) {
+    for (key, item) in defaults.iter() {
+        let value = values.and_then(|v| v.get(key));
+
+        let item = match (table.get_mut(key), item) {
+            (Some(Item::Table(existing)), Item::Table(default)) => {
+                add_missing(existing, default, value.and_then(toml::Value::as_table));
+                continue;
+            }
+            (Some(_), _) => continue,
+            // Новые таблицы записываются после существующих
+            (None, Item::Table(default)) => {
+                let mut new = Table::new();
+                *new.decor_mut() = default.decor().clone();
+                add_missing(&mut new, default, value.and_then(toml::Value::as_table));
+                Item::Table(new)
+            }
+            (None, item) => {
+                let mut item = item.clone();
+                if let Some(default) = item.as_value_mut()
+                    && let Some(value) = value
+                    && let Ok(mut parsed) = value.to_string().parse::<toml_edit::Value>()
+                {
+                    *parsed.decor_mut() = default.decor().clone();
+                    *default = parsed;
+                }
+                item
+            }
+        };
+
+        match defaults.key(key) {
+            Some(k) => table.insert_formatted(k, item),
+            None => table.insert(key, item),
+        };
+    }
+}
+
+/// Запись текущей версии с сохранением комментария к ключу
+fn set_version(doc: &mut DocumentMut) {
+    match doc.get_mut("version").and_then(Item::as_value_mut) {
+        Some(value) => {
+            let decor = value.decor().clone();
+            *value = CONFIG_VERSION.into();
+            *value.decor_mut() = decor;
+        }
+        None => {
+            doc.insert("version", toml_edit::value(CONFIG_VERSION));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_current_default_config() {
+        for lang in [Language::Ru, Language::En] {
+            assert_eq!(upgrade(default_config_str(lang)), None);
+        }
+    }
+
+    #[test]
+    fn adds_missing_keys_without_version_change() {
+        let buf = "version = 1\n\n[browser]\nport = 9000 # свой порт\n";
+        let (version, updated) = upgrade(buf).unwrap();
+        assert_eq!(version, CONFIG_VERSION);
+
+        let cfg: Config = toml::from_str(&updated).unwrap();
+        assert_eq!(cfg.version, CONFIG_VERSION);
+        assert_eq!(cfg.browser.port, 9000);
+        assert!(updated.contains("port = 9000 # свой порт"));
+        assert!(updated.contains("[http_server]"));
+        // Добавленные значения совпадают со значениями по умолчанию при отсутствии ключа
+        assert!(!cfg.launch_options.telegram_notifications);
+
+        // Повторно файл не изменяется
+        assert_eq!(upgrade(&updated), None);
+    }
+
+    #[test]
+    fn sets_version_of_old_files() {
+        let (version, updated) = upgrade("[telegram_bot]\ntoken = \"123:abc\"\n").unwrap();
+        assert_eq!(version, 0);
+
+        let cfg: Config = toml::from_str(&updated).unwrap();
+        assert_eq!(cfg.version, CONFIG_VERSION);
+        assert_eq!(cfg.telegram_bot.token.expose(), "123:abc");
+    }
+
+    #[test]
+    fn skips_newer_or_invalid_files() {
+        assert_eq!(
+            upgrade(&format!("version = {}\n", CONFIG_VERSION + 1)),
+            None
+        );
+        assert_eq!(upgrade("version = "), None);
+    }
+}
@@ -0,0 +1,1151 @@
+mod migrate;
+mod reload;
+mod secrets;
+mod sources;
+mod validate;
+
+pub use migrate::{CONFIG_VERSION, Migration, migrate};
+pub use reload::{reload_and_report, watch_file};
+pub use secrets::Secret;
+pub use sources::{ENV_CONFIG_PATH, Sources};
+
+use crate::{
+    browser::{self, BrowserSessionConfig},
+    error::{Error, Result},
+    i18n::{self, Language, msg},
+    server::ServerConfig,
+    telebot::{
+        types::{ChatId, ChatTarget},
+        webhook::WebhookConfig,
+    },
+    tracking::TrackingOptions,
+};
+use chromiumoxide::browser::HeadlessMode;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::sync::watch;
+
+/// Путь к файлу конфигурации по умолчанию
+pub const CONFIG_PATH: &str = "Config.toml";
+
+/// Текущая конфигурация. Заменяется при перезагрузке
+static CONFIG: OnceLock<watch::Sender<Arc<Config>>> = OnceLock::new();
+
+static SOURCES: OnceLock<Sources> = OnceLock::new();
+
+/// Установка источников конфигурации. Вызывается до первой загрузки
+pub fn init_sources(sources: Sources) {
+    let _ = SOURCES.set(sources);
+}
+
+/// Источники конфигурации
+pub fn sources() -> &'static Sources {
+    SOURCES.get_or_init(Sources::default)
+}
+
+/// Путь в папке данных программы — папке файла конфигурации. Относительные пути профиля браузера,
+/// журналов, `--pidfile` и `dead_letter_file` отсчитываются от неё, а не от текущей папки
+/// (под systemd это `/`)
+pub fn data_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = relative_to(&sources().path, path);
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+fn relative_to(config_path: &Path, path: impl AsRef<Path>) -> PathBuf {
+    config_path.parent().unwrap_or(Path::new("")).join(path)
+}
+
+/// Создание файла конфигурации по умолчанию, если его нет.
+/// При переопределениях из командной строки или окружения файл не обязателен и не создаётся
+pub fn init_if_not() -> Result<bool> {
+    let path = &sources().path;
+    if fs::metadata(path).is_ok() || !sources().overrides.is_empty() {
+        return Ok(false);
+    }
+    fs::write(path, default_config_str(i18n::language()).as_bytes())
+        .map_err(|e| Error::ConfigWrite(path.display().to_string(), e))?;
+    Ok(true)
+}
+
+/// Текущая конфигурация. При первом вызове загружается и проверяется
+pub fn get() -> Result<Arc<Config>> {
+    if let Some(tx) = CONFIG.get() {
+        return Ok(Arc::clone(&tx.borrow()));
+    }
+    let cfg = Arc::new(load(sources())?);
+    let tx = CONFIG.get_or_init(|| watch::channel(cfg).0);
+    Ok(Arc::clone(&tx.borrow()))
+}
+
+/// Подписка на изменения конфигурации после перезагрузки
+pub fn subscribe() -> Result<watch::Receiver<Arc<Config>>> {
+    get()?;
+    Ok(CONFIG.get().unwrap().subscribe())
+}
+
+/// Чтение файла, применение переопределений, разбор и проверка конфигурации.
+/// Если файла нет, переопределения применяются к конфигурации по умолчанию
+pub fn load(sources: &Sources) -> Result<Config> {
+    let path: &Path = &sources.path;
+    let buf = match fs::read_to_string(path) {
+        Ok(buf) => buf,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !sources.overrides.is_empty() => {
+            default_config_str(i18n::language()).to_string()
+        }
+        Err(e) => return Err(Error::ConfigRead(path.display().to_string(), e)),
+    };
+
+    // Текст файла разбирается целиком, чтобы ошибки в нём указывали на строку
+    let mut cfg = toml::from_str::<Config>(&buf)?;
+    if !sources.overrides.is_empty() {
+        let mut table = buf.parse::<toml::Table>()?;
+        let problems = sources.apply(&mut table);
+        if !problems.is_empty() {
+            return Err(Error::InvalidConfig(problems));
+        }
+        cfg = toml::Value::Table(table).try_into::<Config>()?;
+    }
+    i18n::set_language(cfg.language);
+
+    let mut problems = cfg.resolve_secrets();
+    problems.extend(cfg.validate());
+    if !problems.is_empty() {
+        return Err(Error::InvalidConfig(problems));
+    }
+
+    Ok(cfg)
+}
+
+/// Схема конфигурации для проверки переопределений: значения по умолчанию,
+/// у списков — элемент со всеми параметрами (у получателей — параметры всех типов)
+pub(super) fn schema() -> &'static toml::Value {
+    static SCHEMA: OnceLock<toml::Value> = OnceLock::new();
+
+    SCHEMA.get_or_init(|| {
+        let mut account = to_table(&Account::default());
+        // None не записывается в TOML
+        account.insert("max_coefficient".to_string(), toml::Value::Float(0.));
+        let digest = to_table(&Digest {
+            schedule: String::new(),
+            via: DigestVia::default(),
+            email: Email::default(),
+        });
+        let notifier = [
+            Notifier::Console {
+                format: ConsoleFormat::default(),
+            },
+            Notifier::Open {
+                browser: default_open_browser(),
+            },
+            Notifier::Webhook(OutgoingWebhook::default()),
+            Notifier::Desktop {
+                template: String::new(),
+            },
+            Notifier::Email(Email::default()),
+            Notifier::Slack(IncomingWebhook::default()),
+        ]
+        .iter()
+        .flat_map(to_table)
+        .collect::<toml::Table>();
+
+        let mut schema = to_table(&Config::default());
+        for (key, item) in [
+            ("accounts", account),
+            ("digests", digest),
+            ("notifiers", notifier),
+        ] {
+            schema.insert(
+                key.to_string(),
+                toml::Value::Array(vec![toml::Value::Table(item)]),
+            );
+        }
+        toml::Value::Table(schema)
+    })
+}
+
+fn to_table(value: &impl Serialize) -> toml::Table {
+    toml::Table::try_from(value).unwrap_or_default()
+}
+
+/// Конфигурация по умолчанию на выбранном языке
+pub fn default_config_str(lang: Language) -> &'static str {
+    match lang {
+        Language::Ru => DEFAULT_CONFIG_STR_RU,
+        Language::En => DEFAULT_CONFIG_STR_EN,
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Версия схемы конфигурации. 0 — файл, созданный до появления версий
+    pub version: i64,
+    /// Язык сообщений
+    pub language: Language,
+    pub launch_options: LaunchOptions,
+    pub telegram_bot: TelegramBot,
+    pub tracking_supplies: TrackingSupplies,
+    pub browser: Browser,
+    /// Получатели уведомлений. Если не заданы — определяются флагами `launch_options`
+    pub notifiers: Vec<Notifier>,
+    /// Периодические сводки по слотам
+    pub digests: Vec<Digest>,
+    /// Аккаунты продавца. Если не заданы — один аккаунт с параметрами секции `browser`
+    pub accounts: Vec<Account>,
+    pub logging: Logging,
+    pub http_server: HttpServer,
+}
+
+impl Config {
+    /// Список получателей уведомлений с учётом флагов `launch_options`
+    pub fn notifiers(&self) -> Vec<Notifier> {
+        if !self.notifiers.is_empty() {
+            return self.notifiers.clone();
+        }
+
+        let mut notifiers = Vec::new();
+        if self.launch_options.verbose {
+            notifiers.push(Notifier::Console {
+                format: self.launch_options.output,
+            });
+        }
+        if self.launch_options.open {
+            notifiers.push(Notifier::Open {
+                browser: default_open_browser(),
+            });
+        }
+        if self.launch_options.telegram_notifications {
+            notifiers.push(Notifier::Telegram);
+        }
+        notifiers
+    }
+
+    /// Список аккаунтов. Если `accounts` не заданы — один аккаунт без названия
+    pub fn accounts(&self) -> Vec<Account> {
+        if self.accounts.is_empty() {
+            vec![Account::default()]
+        } else {
+            self.accounts.clone()
+        }
+    }
+}
+
+/// Аккаунт продавца со своим профилем браузера, фильтрами и получателями
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Account {
+    /// Название, которое показывается в уведомлениях
+    pub name: String,
+    /// Порт браузера. Если 0 — из секции `browser`
+    pub port: u16,
+    /// Папка профиля браузера. Если пуста — из секции `browser`
+    pub user_data_dir: String,
+    /// ID складов, по которым отправляются уведомления. Если пуст — все склады
+    pub warehouses: Vec<i64>,
+    /// Максимальный коэффициент приёмки. Если не задан — любой
+    pub max_coefficient: Option<f64>,
+    /// Получатели уведомлений телеграм-бота. Если пуст — из секции `telegram_bot`
+    pub targets: Vec<ChatTarget>,
+}
+
+impl Account {
+    /// Параметры браузера аккаунта
+    pub fn browser(&self, browser: &Browser) -> Browser {
+        let mut browser = browser.clone();
+        if self.port != 0 {
+            browser.port = self.port;
+        }
+        if !self.user_data_dir.is_empty() {
+            browser.user_data_dir = self.user_data_dir.clone();
+        }
+        browser
+    }
+
+    /// Параметры опроса с фильтрами аккаунта
+    pub fn to_tracking_options(&self, tracking: &TrackingSupplies) -> TrackingOptions {
+        TrackingOptions {
+            warehouses: self.warehouses.clone(),
+            max_coefficient: self.max_coefficient,
+            ..tracking.to_tracking_options()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchOptions {
+    /// Вход в личный кабинет перед отслеживанием. Устарело: используйте `wbsupplies login`
+    pub first_run: bool,
+    pub telegram_notifications: bool,
+    pub open: bool,
+    pub verbose: bool,
+    /// Формат вывода обновлений при `verbose = true`
+    pub output: ConsoleFormat,
+}
+
+/// Формат вывода обновлений в консоль
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleFormat {
+    /// Текст для чтения человеком
+    #[default]
+    Text,
+    /// JSON Lines: по объекту на каждую открывшуюся или закрывшуюся дату
+    Json,
+}
+
+/// Получатель уведомлений
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Notifier {
+    /// Телеграм-бот (параметры в секции `telegram_bot`)
+    Telegram,
+    /// Вывод в консоль
+    Console {
+        #[serde(default)]
+        format: ConsoleFormat,
+    },
+    /// Открытие поставки в браузере
+    Open {
+        #[serde(default = "default_open_browser")]
+        browser: String,
+    },
+    /// HTTP POST-запрос на указанный адрес
+    Webhook(OutgoingWebhook),
+    /// Системное уведомление рабочего стола
+    Desktop {
+        #[serde(default)]
+        template: String,
+    },
+    /// Письмо со сводкой по появившимся датам
+    Email(Email),
+    /// Входящий webhook Slack. Шаблон задаёт строку с датой
+    Slack(IncomingWebhook),
+    /// Входящий webhook Discord. Шаблон задаёт значение поля с датой
+    Discord(IncomingWebhook),
+}
+
+/// Параметры входящего webhook Slack или Discord
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IncomingWebhook {
+    /// Адрес webhook. Содержит ключ доступа, поэтому хранится как секрет
+    pub url: Secret,
+    /// Файл с адресом (вместо `url`)
+    pub url_file: String,
+    /// Переменная окружения с адресом (вместо `url`)
+    pub url_env: String,
+    /// Путь к шаблону
+    pub template: String,
+}
+
+fn default_open_browser() -> String {
+    "chrome".to_string()
+}
+
+/// Периодическая сводка
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Digest {
+    /// Расписание в формате cron: минута час день месяц день_недели
+    pub schedule: String,
+    /// Способ доставки
+    #[serde(default)]
+    pub via: DigestVia,
+    /// Параметры SMTP для via = "email"
+    #[serde(default)]
+    pub email: Email,
+}
+
+/// Способ доставки сводки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestVia {
+    /// Телеграм-бот (параметры в секции `telegram_bot`)
+    #[default]
+    Telegram,
+    Email,
+}
+
+/// Параметры исходящего webhook. `Debug` скрывает адрес и заголовки
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutgoingWebhook {
+    pub url: String,
+    /// Формат тела запроса
+    pub format: WebhookFormat,
+    /// Путь к файлу шаблона тела запроса (JSON). Если задан — `format` не учитывается
+    pub body_template: String,
+    /// Дополнительные заголовки запроса
+    pub headers: HashMap<String, String>,
+    /// Ключ подписи тела HMAC-SHA256. Если пуст — запрос не подписывается
+    pub secret: Secret,
+    /// Файл с ключом подписи (вместо `secret`)
+    pub secret_file: String,
+    /// Переменная окружения с ключом подписи (вместо `secret`)
+    pub secret_env: String,
+    pub timeout_millis: u64,
+    /// Количество повторных попыток
+    pub retries: u32,
+    /// Начальная задержка между попытками, удваивается с каждой попыткой
+    pub backoff_millis: u64,
+    /// Максимальная задержка между попытками
+    pub max_backoff_millis: u64,
+    /// Файл для событий, которые не удалось доставить, в папке данных. Если пуст — не записываются
+    pub dead_letter_file: String,
+}
+
+impl Default for OutgoingWebhook {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            format: WebhookFormat::default(),
+            body_template: String::new(),
+            headers: HashMap::new(),
+            secret: Secret::default(),
+            secret_file: String::new(),
+            secret_env: String::new(),
+            timeout_millis: 10000,
+            retries: 3,
+            backoff_millis: 1000,
+            max_backoff_millis: 60000,
+            dead_letter_file: String::new(),
+        }
+    }
+}
+
+/// Параметры SMTP для отправки писем
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Email {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: String,
+    pub password: Secret,
+    /// Файл с паролем (вместо `password`)
+    pub password_file: String,
+    /// Переменная окружения с паролем (вместо `password`)
+    pub password_env: String,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Тема письма. Если пуста — тема по умолчанию на языке сообщений
+    pub subject: String,
+    pub timeout_millis: u64,
+    /// Путь к шаблону текстовой части письма
+    pub template: String,
+    /// Путь к шаблону HTML-части письма
+    pub html_template: String,
+}
+
+impl Default for Email {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 587,
+            security: SmtpSecurity::default(),
+            username: String::new(),
+            password: Secret::default(),
+            password_file: String::new(),
+            password_env: String::new(),
+            from: String::new(),
+            to: Vec::new(),
+            subject: String::new(),
+            timeout_millis: 10000,
+            template: String::new(),
+            html_template: String::new(),
+        }
+    }
+}
+
+/// Шифрование SMTP-соединения
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Без шифрования (например, локальный SMTP-сервер для проверки)
+    None,
+    /// STARTTLS
+    #[default]
+    Starttls,
+    /// Неявный TLS (обычно порт 465)
+    Tls,
+}
+
+/// Формат тела исходящего webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// Стабильная схема события
+    #[default]
+    Event,
+    /// Полные данные поставок и себестоимости
+    Raw,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelegramBot {
+    pub token: Secret,
+    /// Файл с токеном (вместо `token`)
+    pub token_file: String,
+    /// Переменная окружения с токеном (вместо `token`)
+    pub token_env: String,
+    /// Пользователи, которым разрешено управлять ботом
+    pub allow_users: Vec<i64>,
+    /// Получатели уведомлений: пользователи, группы, каналы и темы форумов
+    pub targets: Vec<ChatTarget>,
+    /// Прикреплять скриншот страницы поставки к уведомлению
+    pub screenshots: bool,
+    /// CSS-селектор календаря на странице поставки. Если пуст — скриншот всей страницы
+    pub screenshot_selector: String,
+    /// Путь к шаблону уведомления. Если пуст — используется шаблон по умолчанию
+    pub template: String,
+    /// Способ получения обновлений
+    pub mode: UpdatesMode,
+    pub webhook: Webhook,
+}
+
+/// Способ получения обновлений телеграм-бота
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatesMode {
+    #[default]
+    Polling,
+    Webhook,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Webhook {
+    pub url: String,
+    pub listen: String,
+    pub path: String,
+    pub secret_token: Secret,
+    /// Файл с секретным токеном (вместо `secret_token`)
+    pub secret_token_file: String,
+    /// Переменная окружения с секретным токеном (вместо `secret_token`)
+    pub secret_token_env: String,
+    pub tls_cert: String,
+    pub tls_key: String,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            listen: "0.0.0.0:8443".to_string(),
+            path: "/telegram".to_string(),
+            secret_token: Secret::default(),
+            secret_token_file: String::new(),
+            secret_token_env: String::new(),
+            tls_cert: String::new(),
+            tls_key: String::new(),
+        }
+    }
+}
+
+impl Webhook {
+    /// Параметры сервера. Ошибка, если адрес некорректен или не задан секретный токен
+    pub fn to_webhook_config(&self) -> Result<WebhookConfig> {
+        let listen = self.listen.parse().map_err(|_| {
+            Error::InvalidConfig(vec![format!(
+                "telegram_bot.webhook.listen: {} \"{}\"",
+                msg().config_invalid_value,
+                self.listen
+            )])
+        })?;
+        if self.secret_token.is_empty() {
+            return Err(Error::InvalidConfig(vec![format!(
+                "telegram_bot.webhook.secret_token: {}",
+                msg().config_required_for_webhook
+            )]));
+        }
+
+        Ok(WebhookConfig {
+            listen,
+            path: self.path.clone(),
+            secret_token: self.secret_token.expose().to_string(),
+            tls: if self.tls_cert.is_empty() || self.tls_key.is_empty() {
+                None
+            } else {
+                Some((self.tls_cert.clone(), self.tls_key.clone()))
+            },
+        })
+    }
+}
+
+impl TelegramBot {
+    /// Получатели уведомлений. Если `targets` не заданы — уведомления
+    /// отправляются в личные чаты пользователей из `allow_users`
+    pub fn notification_targets(&self) -> Vec<ChatTarget> {
+        if !self.targets.is_empty() {
+            return self.targets.clone();
+        }
+        self.allow_users
+            .iter()
+            .map(|id| ChatTarget::from(ChatId::Id(*id)))
+            .collect()
+    }
+}
+
+/// Журнал работы программы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Logging {
+    /// Уровень (error, warn, info, debug, trace) или фильтр `tracing_subscriber::EnvFilter`
+    pub level: String,
+    pub format: LogFormat,
+    /// Запись журнала в файлы папки `dir`
+    pub file: bool,
+    pub dir: String,
+    pub rotation: LogRotation,
+    /// Сколько файлов журнала хранить. Если 0 — все
+    pub max_files: usize,
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::default(),
+            file: true,
+            dir: "logs".to_string(),
+            rotation: LogRotation::default(),
+            max_files: 7,
+        }
+    }
+}
+
+impl Logging {
+    /// Фильтр событий. Уровень без указания модулей относится только к событиям программы,
+    /// для библиотек выводятся предупреждения и ошибки
+    pub fn filter(&self) -> String {
+        let level = self.level.trim();
+        if level.contains(['=', ',']) {
+            level.to_string()
+        } else {
+            format!("warn,{}={level}", env!("CARGO_CRATE_NAME"))
+        }
+    }
+
+    /// Папка файлов журнала в папке данных
+    pub fn dir_path(&self) -> PathBuf {
+        data_path(&self.dir)
+    }
+}
+
+/// HTTP-сервер состояния и страницы слотов
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpServer {
+    pub enabled: bool,
+    pub listen: String,
+    /// Метрики Prometheus по адресу `/metrics`. Требует `enabled`
+    pub metrics: bool,
+}
+
+impl Default for HttpServer {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "127.0.0.1:8080".to_string(),
+            metrics: false,
+        }
+    }
+}
+
+impl HttpServer {
+    pub fn to_server_config(&self) -> Result<ServerConfig> {
+        let listen = self.listen.parse().map_err(|e| {
+            Error::InvalidConfig(vec![format!("{}: {e}", msg().invalid_http_server_listen)])
+        })?;
+        Ok(ServerConfig {
+            listen,
+            metrics: self.metrics,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Текст для чтения человеком
+    #[default]
+    Pretty,
+    /// Объект JSON в строке
+    Json,
+}
+
+/// Период создания нового файла журнала
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackingSupplies {
+    /// Период отслеживания в днях, не больше 255
+    pub days: u8,
+    pub interval_millis: u64,
+    pub sync_credentials_interval_secs: u64,
+}
+
+impl Default for TrackingSupplies {
+    fn default() -> Self {
+        Self {
+            days: 14,
+            interval_millis: 5000,
+            sync_credentials_interval_secs: 5400,
+        }
+    }
+}
+
+impl TrackingSupplies {
+    pub fn to_tracking_options(&self) -> TrackingOptions {
+        TrackingOptions {
+            days: self.days,
+            update_interval: Duration::from_millis(self.interval_millis),
+            refresh_credentials_interval: Duration::from_secs(self.sync_credentials_interval_secs),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Browser {
+    pub port: u16,
+    pub user_data_dir: String,
+    pub headless: Headless,
+    /// Путь к исполняемому файлу Chrome/Chromium. Если пуст — определяется автоматически
+    pub executable: String,
+    /// Аргументы запуска браузера
+    pub args: Vec<String>,
+    pub sandbox: bool,
+    /// Пути к распакованным расширениям
+    pub extensions: Vec<String>,
+    pub incognito: bool,
+    pub launch_timeout_millis: u64,
+    pub request_timeout_millis: u64,
+    pub cache_enabled: bool,
+    pub timings: BrowserTimings,
+}
+
+impl Default for Browser {
+    fn default() -> Self {
+        let session = BrowserSessionConfig::default();
+        Self {
+            port: 8889,
+            user_data_dir: "user_data".to_string(),
+            headless: Headless::False,
+            executable: String::new(),
+            args: session.args,
+            sandbox: session.sandbox,
+            extensions: session.extensions,
+            incognito: session.incognito,
+            launch_timeout_millis: session.launch_timeout,
+            request_timeout_millis: session.request_timeout,
+            cache_enabled: session.cache_enabled,
+            timings: BrowserTimings::default(),
+        }
+    }
+}
+
+impl Browser {
+    pub fn to_browser_session_config(&self) -> BrowserSessionConfig {
+        let user_data_dir = data_path(&self.user_data_dir);
+        BrowserSessionConfig {
+            executable: Some(self.executable.clone()).filter(|s| !s.is_empty()),
+            user_data_dir: user_data_dir.to_str().map(|v| v.to_string()),
+            args: self.args.clone(),
+            headless: self.headless.into(),
+            sandbox: self.sandbox,
+            extensions: self.extensions.clone(),
+            incognito: self.incognito,
+            port: self.port,
+            launch_timeout: self.launch_timeout_millis,
+            request_timeout: self.request_timeout_millis,
+            cache_enabled: self.cache_enabled,
+            timings: self.timings.to_browser_timings(),
+        }
+    }
+}
+
+/// Режим работы браузера без окна: `true`, `false` или `"new"`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "HeadlessValue", into = "HeadlessValue")]
+pub enum Headless {
+    #[default]
+    False,
+    True,
+    /// Новый headless-режим Chrome (`--headless=new`)
+    New,
+}
+
+/// Представление `Headless` в файле конфигурации
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum HeadlessValue {
+    Bool(bool),
+    Mode(String),
+}
+
+impl TryFrom<HeadlessValue> for Headless {
+    type Error = String;
+
+    fn try_from(value: HeadlessValue) -> std::result::Result<Self, Self::Error> {
+        match value {
+            HeadlessValue::Bool(true) => Ok(Self::True),
+            HeadlessValue::Bool(false) => Ok(Self::False),
+            HeadlessValue::Mode(mode) if mode == "new" => Ok(Self::New),
+            HeadlessValue::Mode(mode) => Err(format!(
+                "{} \"{mode}\": true, false, \"new\"",
+                msg().config_invalid_value
+            )),
+        }
+    }
+}
+
+impl From<Headless> for HeadlessValue {
+    fn from(value: Headless) -> Self {
+        match value {
+            Headless::False => Self::Bool(false),
+            Headless::True => Self::Bool(true),
+            Headless::New => Self::Mode("new".to_string()),
+        }
+    }
+}
+
+impl From<Headless> for HeadlessMode {
+    fn from(value: Headless) -> Self {
+        match value {
+            Headless::False => HeadlessMode::False,
+            Headless::True => HeadlessMode::True,
+            Headless::New => HeadlessMode::New,
+        }
+    }
+}
+
+/// Задержки и таймауты действий в браузере (в миллисекундах)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserTimings {
+    pub launch_sleep_millis: u64,
+    pub set_proxy_sleep_millis: u64,
+    pub action_sleep_millis: u64,
+    pub page_goto_timeout_millis: u64,
+}
+
+impl Default for BrowserTimings {
+    fn default() -> Self {
+        let timings = browser::BrowserTimings::default();
+        Self {
+            launch_sleep_millis: timings.launch_sleep,
+            set_proxy_sleep_millis: timings.set_proxy_sleep,
+            action_sleep_millis: timings.action_sleep,
+            page_goto_timeout_millis: timings.page_goto_timeout,
+        }
+    }
+}
+
+impl BrowserTimings {
+    pub fn to_browser_timings(&self) -> browser::BrowserTimings {
+        browser::BrowserTimings {
+            launch_sleep: self.launch_sleep_millis,
+            set_proxy_sleep: self.set_proxy_sleep_millis,
+            action_sleep: self.action_sleep_millis,
+            page_goto_timeout: self.page_goto_timeout_millis,
+        }
+    }
+}
+
+/// Конфигурация по умолчанию на русском
+const DEFAULT_CONFIG_STR_RU: &str = r##"
+language = "ru" # Язык сообщений: "ru" или "en"
+version = 1 # Версия схемы конфигурации. Обновляется программой автоматически
+
+# Параметры запуска
+[launch_options]
+telegram_notifications = true # Получение уведомлений в телеграм (true/false)
+open = false # Поставка, которая стала доступна в результате отслеживания откроется в браузере (true/false)
+verbose = true # Вывод информации об обновлении поставок в консоль (true/false)
+output = "text" # Формат вывода: "text" или "json" (JSON Lines, по строке на каждую дату)
+
+# Параметры телеграм бота
+[telegram_bot]
+token = "" # Токен бота
+token_file = "" # Или путь к файлу с токеном
+token_env = "" # Или имя переменной окружения с токеном
+allow_users = [] # ID пользователей, которым разрешено управлять ботом ([1234, 4321])
+# Получатели уведомлений. Если не указаны — уведомления получают пользователи из allow_users
+# Пример: [{ chat = 1234 }, { chat = -1001234567890, thread_id = 5 }, { chat = "@channel" }]
+targets = []
+screenshots = false # Прикреплять скриншот страницы поставки к уведомлению (true/false)
+screenshot_selector = "" # CSS-селектор календаря для скриншота. Если пусто — вся страница
+template = "" # Путь к файлу шаблона уведомления (см. README). Если пусто — шаблон по умолчанию
+mode = "polling" # Способ получения обновлений: "polling" или "webhook"
+
+# Параметры webhook (используются при mode = "webhook")
+[telegram_bot.webhook]
+url = "" # Публичный HTTPS-адрес, на который Telegram отправляет обновления
+listen = "0.0.0.0:8443" # Адрес встроенного сервера
+path = "/telegram" # Путь приёма обновлений
+secret_token = "" # Секретный токен для проверки заголовка X-Telegram-Bot-Api-Secret-Token (обязателен)
+secret_token_file = "" # Или путь к файлу с секретным токеном
+secret_token_env = "" # Или имя переменной окружения с секретным токеном
+tls_cert = "" # Путь к сертификату (PEM). Если пусто — сервер работает по HTTP (за обратным прокси)
+tls_key = "" # Путь к закрытому ключу (PEM)
+
+# Параметры отслеживания поставок
+[tracking_supplies]
+days = 14 # За какой период в днях отслеживать поставки (1-255)
+interval_millis = 5000 # Интервал обновления поставок в миллисекундах
+sync_credentials_interval_secs = 5400 # Интервал синхронизации cookie и authorizev3 в секундах
+
+# Параметры браузера
+[browser]
+port = 8889 # Порт браузера
+user_data_dir = "user_data" # Папка профиля браузера, относительно папки файла конфигурации
+headless = false # Скрытый режим работы (true/false/"new")
+executable = "" # Путь к Chrome/Chromium. Если пусто — определяется автоматически
+# args = ["--disable-default-apps", "--no-first-run"] # Аргументы запуска. По умолчанию — набор программы
+sandbox = false # Запуск в песочнице Chrome (true/false)
+extensions = [] # Пути к распакованным расширениям
+incognito = false # Режим инкогнито (true/false)
+launch_timeout_millis = 1500 # Таймаут запуска браузера в миллисекундах
+request_timeout_millis = 2000 # Таймаут запросов к браузеру в миллисекундах
+cache_enabled = true # Кэш браузера (true/false)
+
+# Задержки и таймауты действий в браузере (в миллисекундах)
+[browser.timings]
+launch_sleep_millis = 280 # Пауза после запуска
+set_proxy_sleep_millis = 180 # Пауза после установки прокси
+action_sleep_millis = 80 # Пауза между действиями на странице
+page_goto_timeout_millis = 1400 # Таймаут перехода на страницу
+
+# Журнал работы
+[logging]
+level = "info" # Уровень: "error", "warn", "info", "debug", "trace" или фильтр вида "warn,WbSupplies::tracking=debug"
+format = "pretty" # Формат: "pretty" или "json"
+file = true # Запись журнала в файлы (true/false)
+dir = "logs" # Папка файлов журнала, относительно папки файла конфигурации
+rotation = "daily" # Новый файл: "hourly", "daily" или "never"
+max_files = 7 # Сколько файлов хранить. Если 0 — все
+
+# HTTP-сервер: /health, /supplies, /events и страница с открытыми слотами
+[http_server]
+enabled = false # Запуск сервера (true/false)
+listen = "127.0.0.1:8080" # Адрес сервера. Для доступа из сети — "0.0.0.0:8080"
+metrics = false # Метрики Prometheus по адресу /metrics (true/false)
+
+# Аккаунты продавца. Если не заданы — один аккаунт с параметрами браузера выше.
+# У каждого аккаунта свой профиль браузера и порт; пустые значения берутся из [browser]
+# [[accounts]]
+# name = "Магазин 1" # Название в уведомлениях
+# port = 8889
+# user_data_dir = "user_data_1"
+# warehouses = [] # ID складов для уведомлений. Если пусто — все склады
+# max_coefficient = 1 # Максимальный коэффициент приёмки. Если не указан — любой
+# targets = [] # Получатели в телеграме. Если пусто — из [telegram_bot]
+
+# Получатели уведомлений. Если заданы — флаги telegram_notifications, open и verbose не учитываются
+# Типы: telegram, console, open (browser = "chrome"), webhook, email (см. README), desktop,
+# slack (url = "..."), discord (url = "...")
+# Для desktop, slack, discord и email можно указать template = "путь к шаблону"
+# [[notifiers]]
+# type = "telegram"
+#
+# [[notifiers]]
+# type = "desktop"
+
+# Периодические сводки: открывшиеся слоты по складам, время доступности, минимальный коэффициент
+# и не запланированные поставки. Расписание в формате cron: минута час день месяц день_недели
+# [[digests]]
+# schedule = "0 9 * * *" # Ежедневно в 9:00
+# via = "telegram" # Способ доставки: "telegram" или "email" (параметры SMTP в [digests.email])
+"##;
+
+/// Конфигурация по умолчанию на английском
+const DEFAULT_CONFIG_STR_EN: &str = r##"
+language = "en" # Message language: "ru" or "en"
+version = 1 # Configuration schema version. Updated by the program automatically
+
+# Launch options
+[launch_options]
+telegram_notifications = true # Receive notifications in Telegram (true/false)
+open = false # Open a supply that became available in the browser (true/false)
+verbose = true # Print supply updates to the console (true/false)
+output = "text" # Output format: "text" or "json" (JSON Lines, one line per date)
+
+# Telegram bot options
+[telegram_bot]
+token = "" # Bot token
+token_file = "" # Or a path to a file with the token
+token_env = "" # Or the name of an environment variable with the token
+allow_users = [] # IDs of users allowed to control the bot ([1234, 4321])
+# Notification recipients. If empty, users from allow_users receive notifications
+# Example: [{ chat = 1234 }, { chat = -1001234567890, thread_id = 5 }, { chat = "@channel" }]
+targets = []
+screenshots = false # Attach a screenshot of the supply page to the notification (true/false)
+screenshot_selector = "" # CSS selector of the calendar for the screenshot. If empty, the whole page
+template = "" # Path to the notification template file (see README). If empty, the default template
+mode = "polling" # How updates are received: "polling" or "webhook"
+
+# Webhook options (used when mode = "webhook")
+[telegram_bot.webhook]
+url = "" # Public HTTPS address Telegram sends updates to
+listen = "0.0.0.0:8443" # Address of the built-in server
+path = "/telegram" # Path for incoming updates
+secret_token = "" # Secret token checked against the X-Telegram-Bot-Api-Secret-Token header (required)
+secret_token_file = "" # Or a path to a file with the secret token
+secret_token_env = "" # Or the name of an environment variable with the secret token
+tls_cert = "" # Path to the certificate (PEM). If empty, the server uses plain HTTP (behind a reverse proxy)
+tls_key = "" # Path to the private key (PEM)
+
+# Supply tracking options
+[tracking_supplies]
+days = 14 # Tracking period in days (1-255)
+interval_millis = 5000 # Supply update interval in milliseconds
+sync_credentials_interval_secs = 5400 # Cookie and authorizev3 sync interval in seconds
+
+# Browser options
+[browser]
+port = 8889 # Browser port
+user_data_dir = "user_data" # Browser profile directory, relative to the config file directory
+headless = false # Headless mode (true/false/"new")
+executable = "" # Path to Chrome/Chromium. If empty, detected automatically
+# args = ["--disable-default-apps", "--no-first-run"] # Launch arguments. Defaults to the program's set
+sandbox = false # Run in the Chrome sandbox (true/false)
+extensions = [] # Paths to unpacked extensions
+incognito = false # Incognito mode (true/false)
+launch_timeout_millis = 1500 # Browser launch timeout in milliseconds
+request_timeout_millis = 2000 # Browser request timeout in milliseconds
+cache_enabled = true # Browser cache (true/false)
+
+# Browser action delays and timeouts (in milliseconds)
+[browser.timings]
+launch_sleep_millis = 280 # Pause after launch
+set_proxy_sleep_millis = 180 # Pause after setting a proxy
+action_sleep_millis = 80 # Pause between page actions
+page_goto_timeout_millis = 1400 # Page navigation timeout
+
+# Program log
+[logging]
+level = "info" # Level: "error", "warn", "info", "debug", "trace" or a filter like "warn,WbSupplies::tracking=debug"
+format = "pretty" # Format: "pretty" or "json"
+file = true # Write the log to files (true/false)
+dir = "logs" # Log file directory, relative to the config file directory
+rotation = "daily" # New file: "hourly", "daily" or "never"
+max_files = 7 # How many files to keep. If 0, all of them
+
+# HTTP server: /health, /supplies, /events and a page with open slots
+[http_server]
+enabled = false # Start the server (true/false)
+listen = "127.0.0.1:8080" # Server address. Use "0.0.0.0:8080" for network access
+metrics = false # Prometheus metrics at /metrics (true/false)
+
+# Seller accounts. If not set, a single account with the browser options above.
+# Each account has its own browser profile and port; empty values are taken from [browser]
+# [[accounts]]
+# name = "Store 1" # Name shown in notifications
+# port = 8889
+# user_data_dir = "user_data_1"
+# warehouses = [] # Warehouse IDs to notify about. If empty, all warehouses
+# max_coefficient = 1 # Maximum acceptance coefficient. If not set, any
+# targets = [] # Telegram recipients. If empty, from [telegram_bot]
+
+# Notification recipients. If set, the telegram_notifications, open and verbose flags are ignored
+# Types: telegram, console, open (browser = "chrome"), webhook, email (see README), desktop,
+# slack (url = "..."), discord (url = "...")
+# desktop, slack, discord and email accept template = "path to template"
+# [[notifiers]]
+# type = "telegram"
+#
+# [[notifiers]]
+# type = "desktop"
+
+# Periodic digests: opened slots per warehouse, time open, cheapest coefficient
+# and unplanned supplies. Schedule in cron format: minute hour day month weekday
+# [[digests]]
+# schedule = "0 9 * * *" # Daily at 9:00
+# via = "telegram" # Delivery: "telegram" or "email" (SMTP options in [digests.email])
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Конфигурация по умолчанию с раскомментированными примерами параметров
+    fn parse_with_examples(src: &str) -> Config {
+        let src = src
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(rest)
+                    if rest.starts_with('[')
+                        || rest.split_once(" = ").is_some_and(|(k, _)| {
+                            k.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+                        }) =>
+                {
+                    rest
+                }
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        toml::from_str(&src).unwrap()
+    }
+
+    /// Конфигурация без языка и названий аккаунтов, которые переведены
+    fn normalized(mut cfg: Config) -> toml::Value {
+        cfg.language = Language::default();
+        for account in &mut cfg.accounts {
+            account.name.clear();
+        }
+        toml::Value::try_from(cfg).unwrap()
+    }
+
+    #[test]
+    fn default_configs_match_across_languages() {
+        let ru = default_config_str(Language::Ru);
+        let en = default_config_str(Language::En);
+
+        let parse = |src| normalized(toml::from_str(src).unwrap());
+        assert_eq!(parse(ru), parse(en));
+
+        let ru = parse_with_examples(ru);
+        let en = parse_with_examples(en);
+        assert_eq!(ru.accounts.len(), 1);
+        assert_eq!(ru.digests.len(), 1);
+        assert_eq!(normalized(ru), normalized(en));
+    }
+
+    #[test]
+    fn paths_are_relative_to_config_file() {
+        assert_eq!(
+            relative_to(Path::new("/etc/wbsupplies/Config.toml"), "logs"),
+            Path::new("/etc/wbsupplies/logs")
+        );
+        assert_eq!(
+            relative_to(Path::new("Config.toml"), "user_data"),
+            Path::new("user_data")
+        );
+        assert_eq!(
+            relative_to(
+                Path::new("/etc/wbsupplies/Config.toml"),
+                "/var/log/wbsupplies"
+            ),
+            Path::new("/var/log/wbsupplies")
+        );
+        assert!(data_path("user_data").is_absolute());
+    }
+}
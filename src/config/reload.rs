@@ -0,0 +1,139 @@
+//! Перезагрузка конфигурации во время работы
+
+use serde::Serialize;
+use std::{
+    fs,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, SystemTime},
+};
+
+use super::{CONFIG, Config, get, load, sources};
+use crate::{error::Result, i18n::msg};
+
+/// Интервал проверки изменения файла конфигурации
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Защита от одновременной перезагрузки по сигналу и по изменению файла
+static RELOAD: Mutex<()> = Mutex::new(());
+
+/// Повторная загрузка конфигурации из всех источников.
+/// При ошибке продолжает действовать прежняя конфигурация.
+/// Возвращает `None`, если конфигурация не изменилась, иначе —
+/// изменённые параметры, которые применятся только после перезапуска
+pub fn reload() -> Result<Option<Vec<&'static str>>> {
+    // Замок ничего не хранит, поэтому паника при прошлой перезагрузке не мешает следующим
+    let _guard = RELOAD.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let old = get()?;
+    let new = load(sources())?;
+    if !changed(&*old, &new) {
+        return Ok(None);
+    }
+
+    let restart = restart_required(&old, &new);
+    CONFIG.get().unwrap().send_replace(Arc::new(new));
+    Ok(Some(restart))
+}
+
+/// Перезагрузка с записью результата в журнал.
+/// Чтение и разбор файла блокируют поток, поэтому выполняются вне рабочих потоков tokio
+pub async fn reload_and_report() {
+    let result = match tokio::task::spawn_blocking(reload).await {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!(error = %e, "{}", msg().config_reload_failed);
+            return;
+        }
+    };
+
+    match result {
+        Ok(None) => {}
+        Ok(Some(restart)) => {
+            tracing::info!("{}", msg().config_reloaded);
+            if !restart.is_empty() {
+                tracing::warn!(
+                    keys = %restart.join(", "),
+                    "{}",
+                    msg().config_restart_required
+                );
+            }
+        }
+        Err(e) => tracing::error!(error = %e, "{}", msg().config_reload_failed),
+    }
+}
+
+/// Перезагрузка конфигурации при изменении файла
+pub async fn watch_file() {
+    let modified = || {
+        fs::metadata(&sources().path)
+            .and_then(|m| m.modified())
+            .ok()
+    };
+    let mut last: Option<SystemTime> = modified();
+
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        let current = modified();
+        if current != last {
+            last = current;
+            reload_and_report().await;
+        }
+    }
+}
+
+/// Параметры, которые используются только при запуске
+fn restart_required(old: &Config, new: &Config) -> Vec<&'static str> {
+    let (old_bot, new_bot) = (&old.telegram_bot, &new.telegram_bot);
+
+    [
+        ("language", changed(&old.language, &new.language)),
+        ("browser", changed(&old.browser, &new.browser)),
+        ("accounts", !same_accounts(old, new)),
+        ("telegram_bot.token", old_bot.token != new_bot.token),
+        ("telegram_bot.mode", old_bot.mode != new_bot.mode),
+        (
+            "telegram_bot.webhook",
+            changed(&old_bot.webhook, &new_bot.webhook),
+        ),
+        ("digests", changed(&old.digests, &new.digests)),
+        ("logging", changed(&old.logging, &new.logging)),
+        ("http_server", changed(&old.http_server, &new.http_server)),
+    ]
+    .into_iter()
+    .filter_map(|(key, changed)| changed.then_some(key))
+    .collect()
+}
+
+/// Аккаунты и их браузеры не изменились. Фильтры и получатели применяются без перезапуска
+fn same_accounts(old: &Config, new: &Config) -> bool {
+    let browsers = |cfg: &Config| {
+        cfg.accounts()
+            .into_iter()
+            .map(|a| (a.name, a.port, a.user_data_dir))
+            .collect::<Vec<_>>()
+    };
+    browsers(old) == browsers(new)
+}
+
+/// Сравнение значений по их представлению в TOML
+fn changed<T: Serialize + ?Sized>(old: &T, new: &T) -> bool {
+    toml::Value::try_from(old).ok() != toml::Value::try_from(new).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reloads_after_panic_in_previous_reload() {
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = RELOAD.lock().unwrap();
+            panic!("reload");
+        });
+        assert!(RELOAD.is_poisoned());
+
+        // Ошибка загрузки возвращается, а не заменяется паникой
+        let _ = reload();
+    }
+}
@@ -0,0 +1,357 @@
+//! Секреты конфигурации: значения из файлов и переменных окружения, скрытие в `Debug`
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path};
+
+use super::{Config, DigestVia, Email, Notifier, OutgoingWebhook};
+use crate::i18n::msg;
+
+/// Секретное значение. В `Debug` выводится как `***`
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.trim().is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        redact(&self.0, f)
+    }
+}
+
+fn redact(value: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if value.is_empty() {
+        f.write_str("\"\"")
+    } else {
+        f.write_str("\"***\"")
+    }
+}
+
+/// Строка, выводимая в `Debug` как [`Secret`]
+struct Redacted<'a>(&'a str);
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        redact(self.0, f)
+    }
+}
+
+/// Адрес и заголовки (например, `Authorization`) могут содержать ключи доступа
+impl fmt::Debug for OutgoingWebhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(k, v)| (k, Redacted(v)))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        f.debug_struct("OutgoingWebhook")
+            .field("url", &Redacted(&self.url))
+            .field("format", &self.format)
+            .field("body_template", &self.body_template)
+            .field("headers", &headers)
+            .field("secret", &self.secret)
+            .field("secret_file", &self.secret_file)
+            .field("secret_env", &self.secret_env)
+            .field("timeout_millis", &self.timeout_millis)
+            .field("retries", &self.retries)
+            .field("backoff_millis", &self.backoff_millis)
+            .field("max_backoff_millis", &self.max_backoff_millis)
+            .field("dead_letter_file", &self.dead_letter_file)
+            .finish()
+    }
+}
+
+/// Чтение переменной окружения
+type EnvLookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Источник секрета: значение в файле конфигурации, файл или переменная окружения.
+/// Допускается только один из них
+fn resolve(
+    problems: &mut Vec<String>,
+    env_var: EnvLookup,
+    field: &str,
+    value: &mut Secret,
+    file: &str,
+    env: &str,
+) {
+    let sources = [!value.is_empty(), !file.is_empty(), !env.is_empty()];
+    if sources.into_iter().filter(|s| *s).count() > 1 {
+        let name = field.rsplit('.').next().unwrap_or(field);
+        problems.push(format!(
+            "{field}: {} ({name}, {name}_file, {name}_env)",
+            msg().config_secret_sources
+        ));
+        return;
+    }
+
+    if !file.is_empty() {
+        match fs::read_to_string(file) {
+            Ok(s) => *value = s.trim().to_string().into(),
+            Err(e) => problems.push(format!(
+                "{field}_file: {} \"{file}\": {e}",
+                msg().config_read_failed
+            )),
+        }
+    } else if !env.is_empty() {
+        match env_var(env) {
+            Some(s) => *value = s.trim().to_string().into(),
+            None => problems.push(format!(
+                "{field}_env: {} \"{env}\"",
+                msg().config_env_not_set
+            )),
+        }
+    }
+}
+
+fn resolve_email(problems: &mut Vec<String>, env_var: EnvLookup, prefix: &str, email: &mut Email) {
+    let (file, env) = (email.password_file.clone(), email.password_env.clone());
+    resolve(
+        problems,
+        env_var,
+        &format!("{prefix}.password"),
+        &mut email.password,
+        &file,
+        &env,
+    );
+}
+
+impl Config {
+    /// Подстановка секретов из файлов и переменных окружения.
+    /// Возвращает проблемы в формате проверки конфигурации
+    pub(super) fn resolve_secrets(&mut self) -> Vec<String> {
+        self.resolve_secrets_with(&|name| std::env::var(name).ok())
+    }
+
+    fn resolve_secrets_with(&mut self, env_var: EnvLookup) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let bot = &mut self.telegram_bot;
+        resolve(
+            &mut problems,
+            env_var,
+            "telegram_bot.token",
+            &mut bot.token,
+            &bot.token_file,
+            &bot.token_env,
+        );
+
+        let webhook = &mut bot.webhook;
+        resolve(
+            &mut problems,
+            env_var,
+            "telegram_bot.webhook.secret_token",
+            &mut webhook.secret_token,
+            &webhook.secret_token_file,
+            &webhook.secret_token_env,
+        );
+
+        for (i, notifier) in self.notifiers.iter_mut().enumerate() {
+            let prefix = format!("notifiers[{i}]");
+            match notifier {
+                Notifier::Email(email) => resolve_email(&mut problems, env_var, &prefix, email),
+                Notifier::Webhook(webhook) => resolve(
+                    &mut problems,
+                    env_var,
+                    &format!("{prefix}.secret"),
+                    &mut webhook.secret,
+                    &webhook.secret_file,
+                    &webhook.secret_env,
+                ),
+                Notifier::Slack(webhook) | Notifier::Discord(webhook) => resolve(
+                    &mut problems,
+                    env_var,
+                    &format!("{prefix}.url"),
+                    &mut webhook.url,
+                    &webhook.url_file,
+                    &webhook.url_env,
+                ),
+                _ => {}
+            }
+        }
+        for (i, digest) in self.digests.iter_mut().enumerate() {
+            if digest.via == DigestVia::Email {
+                resolve_email(
+                    &mut problems,
+                    env_var,
+                    &format!("digests[{i}].email"),
+                    &mut digest.email,
+                );
+            }
+        }
+
+        problems
+    }
+
+    /// Предупреждения о секретах, доступных для чтения всем пользователям:
+    /// файлы секретов, файл конфигурации с секретами и данные сессии браузера
+    pub fn secret_warnings(&self, config_path: &Path) -> Vec<String> {
+        let bot = &self.telegram_bot;
+        let emails = self
+            .notifiers
+            .iter()
+            .filter_map(|n| match n {
+                Notifier::Email(email) => Some(email),
+                _ => None,
+            })
+            .chain(
+                self.digests
+                    .iter()
+                    .filter(|d| d.via == DigestVia::Email)
+                    .map(|d| &d.email),
+            )
+            .collect::<Vec<_>>();
+
+        let inline_token =
+            bot.token_file.is_empty() && bot.token_env.is_empty() && !bot.token.is_empty();
+        let inline_password = emails.iter().any(|e| {
+            e.password_file.is_empty() && e.password_env.is_empty() && !e.password.is_empty()
+        });
+        let webhook = &bot.webhook;
+        let inline_webhook_secret = webhook.secret_token_file.is_empty()
+            && webhook.secret_token_env.is_empty()
+            && !webhook.secret_token.is_empty()
+            || self.notifiers.iter().any(|n| match n {
+                Notifier::Webhook(w) => {
+                    w.secret_file.is_empty() && w.secret_env.is_empty() && !w.secret.is_empty()
+                }
+                Notifier::Slack(w) | Notifier::Discord(w) => {
+                    w.url_file.is_empty() && w.url_env.is_empty() && !w.url.is_empty()
+                }
+                _ => false,
+            });
+
+        let mut files = vec![
+            ("telegram_bot.token_file", bot.token_file.as_str()),
+            (
+                "telegram_bot.webhook.secret_token_file",
+                webhook.secret_token_file.as_str(),
+            ),
+        ];
+        files.extend(self.notifiers.iter().filter_map(|n| match n {
+            Notifier::Webhook(w) => Some(("secret_file", w.secret_file.as_str())),
+            Notifier::Slack(w) | Notifier::Discord(w) => Some(("url_file", w.url_file.as_str())),
+            _ => None,
+        }));
+        files.extend(
+            emails
+                .iter()
+                .map(|e| ("password_file", e.password_file.as_str())),
+        );
+        if inline_token || inline_password || inline_webhook_secret {
+            files.push(("--config", config_path.to_str().unwrap_or_default()));
+        }
+        files.push(("browser.user_data_dir", self.browser.user_data_dir.as_str()));
+        files.extend(
+            self.accounts
+                .iter()
+                .map(|a| ("accounts.user_data_dir", a.user_data_dir.as_str())),
+        );
+
+        files
+            .into_iter()
+            .filter(|(field, path)| {
+                // Профиль браузера находится в папке данных
+                let resolved = if field.ends_with("user_data_dir") {
+                    super::data_path(path)
+                } else {
+                    Path::new(path).to_path_buf()
+                };
+                !path.is_empty() && is_world_readable(&resolved)
+            })
+            .map(|(field, path)| format!("{field}: \"{path}\" {}", msg().secret_world_readable))
+            .collect()
+    }
+}
+
+/// Доступ на чтение для всех пользователей
+#[cfg(unix)]
+fn is_world_readable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o004 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_world_readable(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{IncomingWebhook, OutgoingWebhook};
+
+    #[test]
+    fn resolves_webhook_secrets_from_file_and_env() {
+        let file = std::env::temp_dir().join(format!("wbsupplies-secret-{}", std::process::id()));
+        fs::write(&file, "file-secret\n").unwrap();
+        let file = file.display().to_string();
+
+        let mut cfg = Config::default();
+        cfg.telegram_bot.webhook.secret_token_file = file.clone();
+        cfg.notifiers = vec![
+            Notifier::Webhook(OutgoingWebhook {
+                secret_file: file.clone(),
+                ..Default::default()
+            }),
+            Notifier::Slack(IncomingWebhook {
+                url_env: "SLACK_URL".to_string(),
+                ..Default::default()
+            }),
+            Notifier::Discord(IncomingWebhook {
+                url: "https://discord.com/api/webhooks/x".to_string().into(),
+                url_env: "SLACK_URL".to_string(),
+                ..Default::default()
+            }),
+        ];
+
+        let problems = cfg.resolve_secrets_with(&|name| {
+            (name == "SLACK_URL").then(|| "https://hooks.slack.com/x".to_string())
+        });
+        let _ = fs::remove_file(&file);
+
+        assert_eq!(
+            cfg.telegram_bot.webhook.secret_token.expose(),
+            "file-secret"
+        );
+        let Notifier::Webhook(webhook) = &cfg.notifiers[0] else {
+            unreachable!()
+        };
+        assert_eq!(webhook.secret.expose(), "file-secret");
+        let Notifier::Slack(slack) = &cfg.notifiers[1] else {
+            unreachable!()
+        };
+        assert_eq!(slack.url.expose(), "https://hooks.slack.com/x");
+        // Два источника одного секрета
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("notifiers[2].url: "));
+        assert!(!format!("{cfg:?}").contains("hooks.slack.com"));
+    }
+
+    #[test]
+    fn hides_outgoing_webhook_url_and_headers() {
+        let webhook = OutgoingWebhook {
+            url: "https://example.com/hook?key=url-key".to_string(),
+            headers: [("Authorization".to_string(), "Bearer header-key".to_string())].into(),
+            ..Default::default()
+        };
+        let debug = format!("{webhook:?}");
+
+        assert!(debug.contains("Authorization"));
+        assert!(!debug.contains("url-key"));
+        assert!(!debug.contains("header-key"));
+    }
+}
@@ -0,0 +1,446 @@
+//! Источники конфигурации: значения по умолчанию, файл, переменные окружения
+//! `WBSUPPLIES_*` и параметры командной строки — в порядке возрастания приоритета
+
+use std::path::PathBuf;
+use toml::{Table, Value};
+
+use crate::i18n::msg;
+
+/// Префикс переменных окружения
+pub const ENV_PREFIX: &str = "WBSUPPLIES_";
+
+/// Переменная окружения с путём к файлу конфигурации (не является ключом конфигурации)
+pub const ENV_CONFIG_PATH: &str = "WBSUPPLIES_CONFIG";
+
+/// Разделитель уровней вложенности в имени переменной окружения
+const ENV_SEPARATOR: &str = "__";
+
+/// Переопределение значения: (ключ через точку, значение, источник)
+#[derive(Debug, Clone)]
+pub struct Override {
+    pub key: String,
+    pub value: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sources {
+    pub path: PathBuf,
+    /// Переопределения в порядке применения
+    pub overrides: Vec<Override>,
+}
+
+impl Default for Sources {
+    fn default() -> Self {
+        Self::new(super::CONFIG_PATH, Vec::new())
+    }
+}
+
+impl Sources {
+    /// Файл, переменные окружения и переопределения из командной строки
+    pub fn new(path: impl Into<PathBuf>, cli_overrides: Vec<(String, String)>) -> Self {
+        let mut overrides = env_overrides(super::schema());
+        overrides.extend(cli_overrides.into_iter().map(|(key, value)| Override {
+            source: format!("--set {key}"),
+            key,
+            value,
+        }));
+
+        Self {
+            path: path.into(),
+            overrides,
+        }
+    }
+
+    /// Применение переопределений к разобранному файлу.
+    /// Возвращает ошибки в формате проверки конфигурации
+    pub fn apply(&self, table: &mut Table) -> Vec<String> {
+        self.apply_with(super::schema(), table)
+    }
+
+    fn apply_with(&self, schema: &Value, table: &mut Table) -> Vec<String> {
+        self.overrides
+            .iter()
+            .filter_map(|o| {
+                let Some(kind) = kind(schema, &o.key) else {
+                    return Some(format!(
+                        "{}: {} \"{}\"",
+                        o.source,
+                        msg().config_unknown_key,
+                        o.key
+                    ));
+                };
+                set_value(table, &o.key, typed_value(kind, &o.value))
+                    .err()
+                    .map(|e| format!("{}: {e}", o.source))
+            })
+            .collect()
+    }
+}
+
+/// Вид параметра в схеме конфигурации
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// Строка: значение передаётся как есть
+    String,
+    /// Остальные значения разбираются как литерал TOML
+    Literal,
+}
+
+/// Поиск параметра в схеме. `None` — такого параметра нет.
+/// Пустые таблицы (заголовки запроса) принимают любые строковые ключи, пустые массивы — любые элементы
+fn kind(schema: &Value, key: &str) -> Option<Kind> {
+    fn find(value: &Value, parts: &[&str]) -> Option<Kind> {
+        let Some((part, rest)) = parts.split_first() else {
+            return Some(if value.is_str() {
+                Kind::String
+            } else {
+                Kind::Literal
+            });
+        };
+        match value {
+            Value::Table(t) if t.is_empty() => {
+                (!part.is_empty() && rest.is_empty()).then_some(Kind::String)
+            }
+            Value::Table(t) => find(t.get(*part)?, rest),
+            Value::Array(items) => {
+                part.parse::<usize>().ok()?;
+                match items.first() {
+                    Some(item) => find(item, rest),
+                    None => Some(Kind::Literal),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    find(schema, &key.split('.').collect::<Vec<_>>())
+}
+
+/// Значение переопределения с учётом типа параметра: строковые параметры
+/// не разбираются как числа (например, пароль `123456`)
+fn typed_value(kind: Kind, raw: &str) -> Value {
+    match (kind, parse_value(raw)) {
+        (_, Value::String(s)) => Value::String(s),
+        (Kind::String, _) => Value::String(raw.to_string()),
+        (Kind::Literal, value) => value,
+    }
+}
+
+/// Переопределения из переменных окружения:
+/// `WBSUPPLIES_TELEGRAM_BOT__TOKEN` -> `telegram_bot.token`,
+/// `WBSUPPLIES_NOTIFIERS__0__URL` -> `notifiers.0.url`.
+/// Переменные, не соответствующие параметрам (например, секреты для `token_env`), пропускаются
+fn env_overrides(schema: &Value) -> Vec<Override> {
+    let mut vars = std::env::vars()
+        .filter(|(k, _)| k.starts_with(ENV_PREFIX) && k != ENV_CONFIG_PATH)
+        .map(|(k, value)| Override {
+            key: k[ENV_PREFIX.len()..]
+                .to_lowercase()
+                .split(ENV_SEPARATOR)
+                .collect::<Vec<_>>()
+                .join("."),
+            value,
+            source: k,
+        })
+        .filter(|o| kind(schema, &o.key).is_some())
+        .collect::<Vec<_>>();
+    vars.sort_by(|a, b| a.source.cmp(&b.source));
+    vars
+}
+
+/// Разбор значения как литерала TOML (`9000`, `true`, `[1, 2]`), иначе — строка
+pub fn parse_value(raw: &str) -> Value {
+    format!("v = {raw}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Установка значения по ключу через точку. Недостающие таблицы создаются,
+/// числовые части ключа — индексы массивов
+pub fn set_value(table: &mut Table, key: &str, value: Value) -> Result<(), String> {
+    let parts = key.split('.').collect::<Vec<_>>();
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(format!("{} \"{key}\"", msg().config_invalid_key));
+    }
+
+    let (last, path) = parts.split_last().unwrap();
+    let mut current = table;
+
+    for (i, part) in path.iter().enumerate() {
+        let next_is_index = parts[i + 1].parse::<usize>().is_ok();
+        let entry = current.entry(part.to_string()).or_insert_with(|| {
+            if next_is_index {
+                Value::Array(Vec::new())
+            } else {
+                Value::Table(Table::new())
+            }
+        });
+
+        current = match entry {
+            Value::Table(t) => t,
+            Value::Array(items) => {
+                // Индекс массива: part уже пройден, следующая часть — номер элемента
+                return set_in_array(items, &parts[i + 1..], value)
+                    .map_err(|e| format!("{key}: {e}"));
+            }
+            _ => return Err(format!("{} \"{key}\"", msg().config_invalid_key)),
+        };
+    }
+
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+fn set_in_array(items: &mut Vec<Value>, parts: &[&str], value: Value) -> Result<(), String> {
+    let (index, rest) = parts
+        .split_first()
+        .ok_or_else(|| msg().config_invalid_key.to_string())?;
+    let index = index
+        .parse::<usize>()
+        .map_err(|_| format!("{} \"{index}\"", msg().config_invalid_key))?;
+
+    // Допускается добавление элемента в конец массива
+    if index == items.len() {
+        items.push(Value::Table(Table::new()));
+    }
+    let item = items
+        .get_mut(index)
+        .ok_or_else(|| format!("{} {index}", msg().config_index_out_of_range))?;
+
+    if rest.is_empty() {
+        *item = value;
+        return Ok(());
+    }
+
+    match item {
+        Value::Table(t) => set_value(t, &rest.join("."), value),
+        _ => Err(format!(
+            "{} \"{}\"",
+            msg().config_invalid_key,
+            rest.join(".")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(src: &str) -> Table {
+        src.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_toml_literals_or_strings() {
+        assert_eq!(parse_value("9000"), Value::Integer(9000));
+        assert_eq!(parse_value("1.5"), Value::Float(1.5));
+        assert_eq!(parse_value("true"), Value::Boolean(true));
+        assert_eq!(
+            parse_value("[1, 2]"),
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)])
+        );
+        assert_eq!(parse_value("\"123\""), Value::String("123".to_string()));
+        assert_eq!(parse_value("123:abc"), Value::String("123:abc".to_string()));
+        assert_eq!(
+            parse_value("https://example.com"),
+            Value::String("https://example.com".to_string())
+        );
+        assert_eq!(parse_value(""), Value::String(String::new()));
+    }
+
+    #[test]
+    fn sets_nested_tables() {
+        let mut t = table("[browser]\nport = 8889\n");
+
+        set_value(&mut t, "browser.port", Value::Integer(9000)).unwrap();
+        set_value(
+            &mut t,
+            "browser.timings.action_sleep_millis",
+            Value::Integer(10),
+        )
+        .unwrap();
+        set_value(&mut t, "language", Value::String("en".to_string())).unwrap();
+
+        assert_eq!(
+            t,
+            table(
+                "language = \"en\"\n[browser]\nport = 9000\n\
+                 [browser.timings]\naction_sleep_millis = 10\n"
+            )
+        );
+    }
+
+    #[test]
+    fn sets_array_items() {
+        let mut t = table("[[notifiers]]\ntype = \"console\"\n");
+
+        set_value(
+            &mut t,
+            "notifiers.0.format",
+            Value::String("json".to_string()),
+        )
+        .unwrap();
+        // Индекс, равный длине массива, добавляет элемент
+        set_value(
+            &mut t,
+            "notifiers.1.type",
+            Value::String("desktop".to_string()),
+        )
+        .unwrap();
+        set_value(&mut t, "accounts.0.name", Value::String("main".to_string())).unwrap();
+        set_value(
+            &mut t,
+            "browser.args.0",
+            Value::String("--no-sandbox".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            t,
+            table(
+                "browser = { args = [\"--no-sandbox\"] }\n\
+                 [[notifiers]]\ntype = \"console\"\nformat = \"json\"\n\
+                 [[notifiers]]\ntype = \"desktop\"\n\
+                 [[accounts]]\nname = \"main\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_index() {
+        let mut t = table("[[notifiers]]\ntype = \"console\"\n");
+
+        assert!(set_value(&mut t, "notifiers.2.type", Value::Integer(1)).is_err());
+        assert!(set_value(&mut t, "accounts.1.name", Value::Integer(1)).is_err());
+        assert_eq!(
+            t["notifiers"],
+            table("[[notifiers]]\ntype = \"console\"\n")["notifiers"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut t = table("version = 1\nallow = [1]\n");
+
+        assert!(set_value(&mut t, "", Value::Integer(1)).is_err());
+        assert!(set_value(&mut t, "browser..port", Value::Integer(1)).is_err());
+        // Вложенный ключ у значения, которое не является таблицей
+        assert!(set_value(&mut t, "version.major", Value::Integer(1)).is_err());
+        assert!(set_value(&mut t, "allow.0.id", Value::Integer(1)).is_err());
+        assert!(set_value(&mut t, "allow.x", Value::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn applies_overrides_in_order() {
+        let sources = Sources {
+            path: PathBuf::new(),
+            overrides: [
+                ("browser.port", "9000"),
+                ("browser.port", "9001"),
+                ("bad..key", "1"),
+            ]
+            .into_iter()
+            .map(|(key, value)| Override {
+                key: key.to_string(),
+                value: value.to_string(),
+                source: format!("--set {key}"),
+            })
+            .collect(),
+        };
+        let mut t = Table::new();
+
+        let problems = sources.apply(&mut t);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("--set bad..key: "));
+        assert_eq!(t, table("[browser]\nport = 9001\n"));
+    }
+
+    fn schema() -> Value {
+        Value::Table(table(
+            "language = \"ru\"\n\
+             [browser]\nport = 8889\nargs = []\n\
+             [[notifiers]]\ntype = \"\"\nurl = \"\"\nheaders = {}\n\
+             [[digests]]\n[digests.email]\npassword = \"\"\n",
+        ))
+    }
+
+    #[test]
+    fn finds_keys_in_schema() {
+        let schema = schema();
+
+        assert_eq!(kind(&schema, "browser.port"), Some(Kind::Literal));
+        assert_eq!(kind(&schema, "browser.args.0"), Some(Kind::Literal));
+        assert_eq!(kind(&schema, "notifiers.0.url"), Some(Kind::String));
+        assert_eq!(
+            kind(&schema, "notifiers.0.headers.Authorization"),
+            Some(Kind::String)
+        );
+        assert_eq!(
+            kind(&schema, "digests.1.email.password"),
+            Some(Kind::String)
+        );
+
+        assert_eq!(kind(&schema, "bot_token"), None);
+        assert_eq!(kind(&schema, "browser.unknown"), None);
+        assert_eq!(kind(&schema, "notifiers.x.url"), None);
+        assert_eq!(kind(&schema, "browser.port.x"), None);
+    }
+
+    #[test]
+    fn keeps_strings_for_string_settings() {
+        let sources = Sources {
+            path: PathBuf::new(),
+            overrides: [
+                ("digests.0.email.password", "123456"),
+                ("language", "\"en\""),
+                ("browser.port", "9000"),
+                ("bot_token", "123:abc"),
+            ]
+            .into_iter()
+            .map(|(key, value)| Override {
+                key: key.to_string(),
+                value: value.to_string(),
+                source: format!("--set {key}"),
+            })
+            .collect(),
+        };
+        let mut t = Table::new();
+
+        let problems = sources.apply_with(&schema(), &mut t);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("--set bot_token: "));
+        assert_eq!(
+            t,
+            table(
+                "language = \"en\"\n[browser]\nport = 9000\n\
+                 [[digests]]\n[digests.email]\npassword = \"123456\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn schema_covers_default_config() {
+        let schema = crate::config::schema();
+
+        for key in [
+            "telegram_bot.token",
+            "telegram_bot.webhook.secret_token",
+            "accounts.0.max_coefficient",
+            "accounts.0.user_data_dir",
+            "notifiers.0.type",
+            "notifiers.0.body_template",
+            "notifiers.0.password_env",
+            "notifiers.0.format",
+            "digests.0.schedule",
+            "digests.0.email.to",
+            "logging.dir",
+        ] {
+            assert!(kind(schema, key).is_some(), "{key}");
+        }
+        assert_eq!(kind(schema, "notifiers.0.password"), Some(Kind::String));
+        assert_eq!(kind(schema, "tracking_supplies.days"), Some(Kind::Literal));
+    }
+}
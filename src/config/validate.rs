@@ -0,0 +1,264 @@
+use lettre::message::Mailbox;
+use reqwest::header::{HeaderName, HeaderValue};
+use std::{collections::HashSet, net::SocketAddr};
+use tracing_subscriber::EnvFilter;
+
+use super::{CONFIG_VERSION, Config, DigestVia, Email, Notifier, UpdatesMode};
+use crate::{digest::Schedule, i18n::msg, notify::template::Template};
+
+/// Список проблем конфигурации в виде `поле: описание`
+#[derive(Debug, Default)]
+struct Problems(Vec<String>);
+
+impl Problems {
+    fn push(&mut self, field: impl AsRef<str>, problem: impl AsRef<str>) {
+        self.0
+            .push(format!("{}: {}", field.as_ref(), problem.as_ref()));
+    }
+
+    fn non_empty(&mut self, field: impl AsRef<str>, value: &str) {
+        if value.trim().is_empty() {
+            self.push(field, msg().config_empty);
+        }
+    }
+
+    fn positive(&mut self, field: impl AsRef<str>, value: u64) {
+        if value == 0 {
+            self.push(field, msg().config_must_be_positive);
+        }
+    }
+
+    fn template(&mut self, field: impl AsRef<str>, path: &str) {
+        if let Err(e) = Template::load(path, "") {
+            self.push(field, e);
+        }
+    }
+
+    fn email(&mut self, prefix: &str, email: &Email) {
+        self.non_empty(format!("{prefix}.host"), &email.host);
+        self.positive(format!("{prefix}.port"), email.port.into());
+
+        if email.from.parse::<Mailbox>().is_err() {
+            self.push(
+                format!("{prefix}.from"),
+                format!("{} \"{}\"", msg().config_invalid_value, email.from),
+            );
+        }
+        if email.to.is_empty() {
+            self.push(format!("{prefix}.to"), msg().config_empty);
+        }
+        for (i, to) in email.to.iter().enumerate() {
+            if to.parse::<Mailbox>().is_err() {
+                self.push(
+                    format!("{prefix}.to[{i}]"),
+                    format!("{} \"{to}\"", msg().config_invalid_value),
+                );
+            }
+        }
+
+        self.template(format!("{prefix}.template"), &email.template);
+        self.template(format!("{prefix}.html_template"), &email.html_template);
+    }
+}
+
+impl Config {
+    /// Проверка значений конфигурации. Возвращает все найденные проблемы
+    pub fn validate(&self) -> Vec<String> {
+        let mut p = Problems::default();
+        if self.version > CONFIG_VERSION {
+            p.push(
+                "version",
+                format!("{} ({CONFIG_VERSION})", msg().config_version_unsupported),
+            );
+        }
+        let notifiers = self.notifiers();
+
+        let telegram_used = notifiers.iter().any(|n| matches!(n, Notifier::Telegram))
+            || self.digests.iter().any(|d| d.via == DigestVia::Telegram);
+
+        // Телеграм-бот
+        let bot = &self.telegram_bot;
+        if telegram_used {
+            if bot.token.is_empty() {
+                p.push("telegram_bot.token", msg().config_required_for_telegram);
+            }
+            // Получатели могут быть заданы отдельно для каждого аккаунта
+            if bot.notification_targets().is_empty()
+                && self.accounts().iter().any(|a| a.targets.is_empty())
+            {
+                p.push("telegram_bot.targets", msg().config_no_recipients);
+            }
+            p.template("telegram_bot.template", &bot.template);
+        }
+
+        if bot.mode == UpdatesMode::Webhook {
+            let webhook = &bot.webhook;
+            if webhook.url.trim().is_empty() {
+                p.push(
+                    "telegram_bot.webhook.url",
+                    msg().config_required_for_webhook,
+                );
+            }
+            if webhook.listen.parse::<SocketAddr>().is_err() {
+                p.push(
+                    "telegram_bot.webhook.listen",
+                    format!("{} \"{}\"", msg().config_invalid_value, webhook.listen),
+                );
+            }
+            // Без токена сервер принимал бы обновления от кого угодно
+            if webhook.secret_token.is_empty() {
+                p.push(
+                    "telegram_bot.webhook.secret_token",
+                    msg().config_required_for_webhook,
+                );
+            }
+            if !webhook.path.starts_with('/') {
+                p.push("telegram_bot.webhook.path", msg().config_path_slash);
+            }
+            if webhook.tls_cert.is_empty() != webhook.tls_key.is_empty() {
+                p.push("telegram_bot.webhook.tls_cert", msg().config_tls_pair);
+            }
+        }
+
+        // Отслеживание и браузер
+        let tracking = &self.tracking_supplies;
+        p.positive("tracking_supplies.days", tracking.days.into());
+        p.positive(
+            "tracking_supplies.interval_millis",
+            tracking.interval_millis,
+        );
+        p.positive(
+            "tracking_supplies.sync_credentials_interval_secs",
+            tracking.sync_credentials_interval_secs,
+        );
+        p.positive("browser.port", self.browser.port.into());
+        p.non_empty("browser.user_data_dir", &self.browser.user_data_dir);
+        p.positive(
+            "browser.launch_timeout_millis",
+            self.browser.launch_timeout_millis,
+        );
+        p.positive(
+            "browser.request_timeout_millis",
+            self.browser.request_timeout_millis,
+        );
+
+        // Аккаунты: у каждого свой браузер и профиль
+        let accounts = self.accounts();
+        let mut seen = HashSet::new();
+        for (i, account) in accounts.iter().enumerate() {
+            let prefix = format!("accounts[{i}]");
+            let browser = account.browser(&self.browser);
+
+            if accounts.len() > 1 && account.name.trim().is_empty() {
+                p.push(format!("{prefix}.name"), msg().account_name_required);
+            }
+            for (field, value) in [
+                ("name", account.name.clone()),
+                ("port", browser.port.to_string()),
+                ("user_data_dir", browser.user_data_dir),
+            ] {
+                if !value.is_empty() && !seen.insert((field, value.clone())) {
+                    p.push(
+                        format!("{prefix}.{field}"),
+                        format!("{} \"{value}\"", msg().config_duplicate),
+                    );
+                }
+            }
+        }
+
+        // Журнал
+        if let Err(e) = EnvFilter::try_new(self.logging.filter()) {
+            p.push("logging.level", e.to_string());
+        }
+        if self.logging.file {
+            p.non_empty("logging.dir", &self.logging.dir);
+        }
+
+        // HTTP-сервер. Адрес проверяется и у выключенного сервера: его могут включить при перезагрузке
+        let server = &self.http_server;
+        if server.listen.parse::<SocketAddr>().is_err() {
+            p.push(
+                "http_server.listen",
+                format!("{} \"{}\"", msg().config_invalid_value, server.listen),
+            );
+        }
+        // Метрики отдаются тем же сервером
+        if server.metrics && !server.enabled {
+            p.push("http_server.metrics", msg().config_metrics_requires_server);
+        }
+
+        // Получатели уведомлений
+        for (i, notifier) in self.notifiers.iter().enumerate() {
+            let prefix = format!("notifiers[{i}]");
+            match notifier {
+                Notifier::Telegram | Notifier::Console { .. } => {}
+                Notifier::Open { browser } => p.non_empty(format!("{prefix}.browser"), browser),
+                Notifier::Webhook(webhook) => {
+                    p.non_empty(format!("{prefix}.url"), &webhook.url);
+                    p.positive(format!("{prefix}.timeout_millis"), webhook.timeout_millis);
+                    p.template(format!("{prefix}.body_template"), &webhook.body_template);
+                    for (name, value) in &webhook.headers {
+                        if HeaderName::from_bytes(name.as_bytes()).is_err()
+                            || HeaderValue::from_str(value).is_err()
+                        {
+                            p.push(
+                                format!("{prefix}.headers.{name}"),
+                                format!("{} \"{name}: {value}\"", msg().config_invalid_value),
+                            );
+                        }
+                    }
+                }
+                Notifier::Desktop { template } => {
+                    p.template(format!("{prefix}.template"), template)
+                }
+                Notifier::Email(email) => p.email(&prefix, email),
+                Notifier::Slack(webhook) | Notifier::Discord(webhook) => {
+                    p.non_empty(format!("{prefix}.url"), webhook.url.expose());
+                    p.template(format!("{prefix}.template"), &webhook.template);
+                }
+            }
+        }
+
+        // Сводки
+        for (i, digest) in self.digests.iter().enumerate() {
+            let prefix = format!("digests[{i}]");
+            if let Err(e) = digest.schedule.parse::<Schedule>() {
+                p.push(format!("{prefix}.schedule"), e);
+            }
+            if digest.via == DigestVia::Email {
+                p.email(&format!("{prefix}.email"), &digest.email);
+            }
+        }
+
+        p.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_problem(cfg: &Config, field: &str) -> bool {
+        cfg.validate()
+            .iter()
+            .any(|p| p.starts_with(&format!("{field}:")))
+    }
+
+    #[test]
+    fn metrics_require_http_server() {
+        let mut cfg = Config::default();
+        cfg.http_server.metrics = true;
+        assert!(has_problem(&cfg, "http_server.metrics"));
+
+        cfg.http_server.enabled = true;
+        assert!(!has_problem(&cfg, "http_server.metrics"));
+    }
+
+    #[test]
+    fn checks_listen_of_disabled_http_server() {
+        let mut cfg = Config::default();
+        cfg.http_server.listen = "localhost".to_string();
+        assert!(has_problem(&cfg, "http_server.listen"));
+        assert!(cfg.http_server.to_server_config().is_err());
+    }
+}
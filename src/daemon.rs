@@ -0,0 +1,110 @@
+//! Работа службой: уведомления systemd (sd_notify) и файл с PID процесса
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    error::{Error, Result},
+    tracking::Trackers,
+};
+
+/// Файл с PID процесса. Удаляется при завершении
+pub struct Pidfile(PathBuf);
+
+impl Pidfile {
+    pub fn create(path: &Path) -> Result<Self> {
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .map_err(|e| Error::Pidfile(path.display().to_string(), e))?;
+        Ok(Self(path.to_path_buf()))
+    }
+}
+
+impl Drop for Pidfile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Уведомление systemd о готовности после первого успешного опроса всех аккаунтов
+/// и отправка WATCHDOG=1, пока опросы успешны. Без systemd (нет `NOTIFY_SOCKET`) ничего не делает
+pub fn notify_ready(trackers: &Arc<Trackers>) {
+    #[cfg(unix)]
+    {
+        if std::env::var_os("NOTIFY_SOCKET").is_none() {
+            return;
+        }
+        let mut usec = 0;
+        let watchdog = sd_notify::watchdog_enabled(false, &mut usec)
+            .then(|| std::time::Duration::from_micros(usec));
+        tokio::spawn(systemd::run(Arc::clone(trackers), watchdog));
+    }
+
+    #[cfg(not(unix))]
+    let _ = trackers;
+}
+
+/// Уведомление systemd о начале завершения
+pub fn notify_stopping() {
+    #[cfg(unix)]
+    systemd::notify(&[sd_notify::NotifyState::Stopping]);
+}
+
+#[cfg(unix)]
+mod systemd {
+    use chrono::Local;
+    use sd_notify::NotifyState;
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use crate::{i18n::msg, tracking::Trackers};
+
+    pub fn notify(state: &[NotifyState]) {
+        if let Err(e) = sd_notify::notify(false, state) {
+            tracing::warn!(error = %e, "{}", msg().systemd_notify_failed);
+        }
+    }
+
+    /// WATCHDOG=1 отправляется дважды за период `watchdog`, если все аккаунты
+    /// успешно опрошены за последний период. Иначе systemd перезапустит программу
+    pub async fn run(trackers: Arc<Trackers>, watchdog: Option<Duration>) {
+        let mut ready = false;
+        let mut pinged = Instant::now();
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+
+        loop {
+            interval.tick().await;
+
+            // Время опроса аккаунта, который дольше всех не опрашивался успешно.
+            // None, если какой-то аккаунт ещё не опрошен
+            let mut oldest = Some(Local::now());
+            for t in trackers.iter() {
+                oldest = oldest.min(t.supplies.status().await.last_success);
+            }
+            let Some(oldest) = oldest else {
+                continue;
+            };
+
+            if !ready {
+                ready = true;
+                notify(&[
+                    NotifyState::Ready,
+                    NotifyState::Status(msg().tracking_started),
+                ]);
+            }
+
+            if let Some(timeout) = watchdog
+                && pinged.elapsed() >= timeout / 2
+                && (Local::now() - oldest)
+                    .to_std()
+                    .is_ok_and(|age| age < timeout)
+            {
+                notify(&[NotifyState::Watchdog]);
+                pinged = Instant::now();
+            }
+        }
+    }
+}
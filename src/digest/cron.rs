@@ -0,0 +1,317 @@
+//! Расписание в формате cron: `минута час день месяц день_недели`.
+//!
+//! Поддерживаются `*`, списки `1,15`, диапазоны `1-5`, шаги `*/10` и `8-20/2`,
+//! а также сокращения `@hourly`, `@daily`, `@weekly` и `@monthly`.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::i18n::msg;
+
+/// Максимальный горизонт поиска следующего запуска
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    /// День месяца ограничен (поле охватывает не все дни)
+    days_restricted: bool,
+    /// День недели ограничен (поле охватывает не все дни недели)
+    weekdays_restricted: bool,
+}
+
+impl std::str::FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            expr => expr,
+        };
+
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(format!("{}: {s}", msg().cron_field_count));
+        };
+
+        let mut weekdays_set = parse_field(weekdays, 0, 7)?;
+        // 7 — тоже воскресенье
+        if weekdays_set[7] {
+            weekdays_set[0] = true;
+        }
+        weekdays_set.truncate(7);
+
+        let days = parse_field(days, 1, 31)?;
+
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days_restricted: !days[1..].iter().all(|d| *d),
+            days,
+            months: parse_field(months, 1, 12)?,
+            weekdays_restricted: !weekdays_set.iter().all(|d| *d),
+            weekdays: weekdays_set,
+        })
+    }
+}
+
+/// Разбор поля в набор допустимых значений, индексируемый значением
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut set = vec![false; max as usize + 1];
+    let parse = |v: &str| {
+        v.parse::<u32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(|| format!("{} {v} ({min}-{max})", msg().cron_invalid_value))
+    };
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("{} {step}", msg().cron_invalid_step))?,
+            ),
+            None => (part, 1),
+        };
+
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (parse(a)?, parse(b)?),
+                None => {
+                    let v = parse(range)?;
+                    // `5/15` — с 5 до конца с шагом 15
+                    (v, if step > 1 { max } else { v })
+                }
+            },
+        };
+
+        if from > to {
+            return Err(format!("{} {range}", msg().cron_invalid_range));
+        }
+        for v in (from..=to).step_by(step as usize) {
+            set[v as usize] = true;
+        }
+    }
+
+    Ok(set)
+}
+
+impl Schedule {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !self.months[date.month() as usize] {
+            return false;
+        }
+        let day = self.days[date.day() as usize];
+        let weekday = self.weekdays[date.weekday().num_days_from_sunday() as usize];
+
+        // Как в cron: если ограничены оба поля — достаточно совпадения любого
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// Ближайший момент запуска строго после `after`
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+        let mut t: NaiveDateTime = start;
+
+        while t < limit {
+            if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours[t.hour() as usize] {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes[t.minute() as usize] {
+                t += Duration::minutes(1);
+                continue;
+            }
+
+            // Время, пропущенное при переходе на летнее время, не существует
+            match t.and_local_timezone(Local).earliest() {
+                Some(time) if time > after => return Some(time),
+                _ => t += Duration::minutes(1),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn values(set: &[bool]) -> Vec<usize> {
+        (0..set.len()).filter(|i| set[*i]).collect()
+    }
+
+    /// Несколько следующих запусков после `after`
+    fn next(expr: &str, after: DateTime<Local>, count: usize) -> Vec<DateTime<Local>> {
+        let schedule = expr.parse::<Schedule>().unwrap();
+        std::iter::successors(schedule.next_after(after), |t| schedule.next_after(*t))
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn parses_lists_ranges_and_steps() {
+        assert_eq!(values(&parse_field("1,15", 1, 31).unwrap()), [1, 15]);
+        assert_eq!(values(&parse_field("1-5", 0, 6).unwrap()), [1, 2, 3, 4, 5]);
+        assert_eq!(
+            values(&parse_field("*/15", 0, 59).unwrap()),
+            [0, 15, 30, 45]
+        );
+        assert_eq!(
+            values(&parse_field("8-20/4", 0, 23).unwrap()),
+            [8, 12, 16, 20]
+        );
+        assert_eq!(values(&parse_field("50/5", 0, 59).unwrap()), [50, 55]);
+        assert_eq!(
+            values(&parse_field("0-2,10-11", 0, 23).unwrap()),
+            [0, 1, 2, 10, 11]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_fields() {
+        assert!(parse_field("60", 0, 59).is_err());
+        assert!(parse_field("0", 1, 31).is_err());
+        assert!(parse_field("5-1", 0, 59).is_err());
+        assert!(parse_field("*/0", 0, 59).is_err());
+        assert!(parse_field("a", 0, 59).is_err());
+        assert!("0 9 * *".parse::<Schedule>().is_err());
+        assert!("0 9 * * * *".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn parses_aliases_and_sunday() {
+        assert_eq!(
+            "@weekly".parse::<Schedule>(),
+            "0 0 * * 0".parse::<Schedule>()
+        );
+        assert_eq!(
+            "@daily".parse::<Schedule>(),
+            "0 0 * * *".parse::<Schedule>()
+        );
+        assert_eq!(
+            "0 0 * * 7".parse::<Schedule>(),
+            "0 0 * * 0".parse::<Schedule>()
+        );
+    }
+
+    #[test]
+    fn restricts_only_partial_day_fields() {
+        let full = "0 9 1-31 * 0-6".parse::<Schedule>().unwrap();
+        assert!(!full.days_restricted && !full.weekdays_restricted);
+
+        let star_step = "0 9 */1 * 1-7".parse::<Schedule>().unwrap();
+        assert!(!star_step.days_restricted && !star_step.weekdays_restricted);
+
+        let every_other = "0 9 */2 * 1".parse::<Schedule>().unwrap();
+        assert!(every_other.days_restricted && every_other.weekdays_restricted);
+    }
+
+    #[test]
+    fn finds_next_minute_and_hour() {
+        assert_eq!(
+            next("*/15 * * * *", at(2026, 10, 18, 10, 7), 3),
+            [
+                at(2026, 10, 18, 10, 15),
+                at(2026, 10, 18, 10, 30),
+                at(2026, 10, 18, 10, 45)
+            ]
+        );
+        // Строго после: текущая минута не подходит
+        assert_eq!(
+            next("0 9 * * *", at(2026, 10, 18, 9, 0), 1),
+            [at(2026, 10, 19, 9, 0)]
+        );
+        assert_eq!(
+            next("30 8-20/6 * * *", at(2026, 10, 18, 15, 0), 3),
+            [
+                at(2026, 10, 18, 20, 30),
+                at(2026, 10, 19, 8, 30),
+                at(2026, 10, 19, 14, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn weekday_only_when_day_of_month_is_unrestricted() {
+        // 2026-10-18 — воскресенье
+        assert_eq!(
+            next("0 9 * * 1", at(2026, 10, 18, 12, 0), 2),
+            [at(2026, 10, 19, 9, 0), at(2026, 10, 26, 9, 0)]
+        );
+        assert_eq!(
+            next("0 9 1-31 * 1", at(2026, 10, 18, 12, 0), 2),
+            [at(2026, 10, 19, 9, 0), at(2026, 10, 26, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_weekday_when_both_restricted() {
+        // 1 ноября 2026 — воскресенье, подходит по дню месяца
+        assert_eq!(
+            next("0 9 1 * 1", at(2026, 10, 18, 12, 0), 3),
+            [
+                at(2026, 10, 19, 9, 0),
+                at(2026, 10, 26, 9, 0),
+                at(2026, 11, 1, 9, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn rolls_over_months_and_years() {
+        assert_eq!(
+            next("30 12 1 * *", at(2026, 1, 31, 13, 0), 2),
+            [at(2026, 2, 1, 12, 30), at(2026, 3, 1, 12, 30)]
+        );
+        assert_eq!(
+            next("0 0 31 * *", at(2026, 1, 31, 0, 0), 2),
+            [at(2026, 3, 31, 0, 0), at(2026, 5, 31, 0, 0)]
+        );
+        assert_eq!(
+            next("0 0 1 1 *", at(2026, 10, 18, 0, 0), 1),
+            [at(2027, 1, 1, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn finds_february_29() {
+        assert_eq!(
+            next("0 0 29 2 *", at(2026, 3, 1, 0, 0), 2),
+            [at(2028, 2, 29, 0, 0), at(2032, 2, 29, 0, 0)]
+        );
+        // 30 февраля не бывает
+        assert_eq!(
+            "0 0 30 2 *"
+                .parse::<Schedule>()
+                .unwrap()
+                .next_after(at(2026, 1, 1, 0, 0)),
+            None
+        );
+    }
+}
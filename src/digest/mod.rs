@@ -0,0 +1,430 @@
+//! Периодические сводки по слотам приёмки
+
+mod cron;
+
+pub use cron::Schedule;
+
+use chrono::{DateTime, Local, TimeDelta};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::Mutex;
+
+use crate::{
+    config::{self, Config},
+    i18n::msg,
+    notify::{EmailNotifier, Event, account_label, template::escape_html},
+    telebot::{self, Bot, BotBuilder},
+    tracking::Trackers,
+    util,
+};
+
+/// Сколько хранить закрывшиеся слоты
+const RETENTION: TimeDelta = TimeDelta::days(35);
+
+/// Дата приёмки поставки, ставшая доступной
+#[derive(Debug, Clone)]
+struct Slot {
+    preorder_id: i64,
+    warehouse: String,
+    date: String,
+    coefficient: f64,
+    opened_at: DateTime<Local>,
+    closed_at: Option<DateTime<Local>>,
+}
+
+/// Журнал слотов, наблюдаемых трекером
+#[derive(Debug, Default)]
+pub struct SlotLog {
+    slots: Mutex<Vec<Slot>>,
+}
+
+impl SlotLog {
+    async fn record(&self, event: &Event) {
+        let mut slots = self.slots.lock().await;
+
+        for (k, v) in &event.updates {
+            for c in &v.costs {
+                slots.push(Slot {
+                    preorder_id: *k,
+                    warehouse: event.label(&v.supply.warehouse_name),
+                    date: c.date.clone(),
+                    coefficient: c.coefficient,
+                    opened_at: event.time,
+                    closed_at: None,
+                });
+            }
+            for c in &v.closed_costs {
+                if let Some(slot) = slots
+                    .iter_mut()
+                    .rev()
+                    .find(|s| s.preorder_id == *k && s.date == c.date && s.closed_at.is_none())
+                {
+                    slot.closed_at = Some(event.time);
+                }
+            }
+        }
+
+        // Слоты, открывшиеся раньше срока хранения, в сводки уже не попадают,
+        // даже если так и не закрылись (например, поставку запланировали)
+        let expired = event.time - RETENTION;
+        slots.retain(|s| s.opened_at > expired);
+    }
+
+    /// Статистика по складам за период
+    async fn stats(&self, from: DateTime<Local>, to: DateTime<Local>) -> Vec<WarehouseStats> {
+        let slots = self.slots.lock().await;
+        let mut stats: BTreeMap<&str, WarehouseStats> = BTreeMap::new();
+
+        for slot in slots
+            .iter()
+            .filter(|s| s.opened_at >= from && s.opened_at < to)
+        {
+            let duration = slot.closed_at.unwrap_or(to).min(to) - slot.opened_at;
+            let entry = stats
+                .entry(&slot.warehouse)
+                .or_insert_with(|| WarehouseStats {
+                    warehouse: slot.warehouse.clone(),
+                    opened: 0,
+                    total_open: TimeDelta::zero(),
+                    longest_open: TimeDelta::zero(),
+                    min_coefficient: slot.coefficient,
+                });
+
+            entry.opened += 1;
+            entry.total_open += duration;
+            entry.longest_open = entry.longest_open.max(duration);
+            entry.min_coefficient = entry.min_coefficient.min(slot.coefficient);
+        }
+
+        stats.into_values().collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WarehouseStats {
+    warehouse: String,
+    /// Количество открывшихся слотов
+    opened: usize,
+    total_open: TimeDelta,
+    longest_open: TimeDelta,
+    min_coefficient: f64,
+}
+
+/// Сводка за период
+#[derive(Debug, Clone)]
+struct Report {
+    from: DateTime<Local>,
+    to: DateTime<Local>,
+    warehouses: Vec<WarehouseStats>,
+    /// Не запланированные поставки: (preorder_id, склад)
+    unplanned: Vec<(i64, String)>,
+}
+
+impl Report {
+    fn new(
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        warehouses: Vec<WarehouseStats>,
+        mut unplanned: Vec<(i64, String)>,
+    ) -> Self {
+        unplanned.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        Self {
+            from,
+            to,
+            warehouses,
+            unplanned,
+        }
+    }
+
+    /// Текст сводки. При `html = true` — с разметкой Telegram HTML
+    fn render(&self, html: bool) -> String {
+        let bold = |s: &str| {
+            if html {
+                format!("<b>{}</b>", escape_html(s))
+            } else {
+                s.to_string()
+            }
+        };
+
+        let m = msg();
+        let mut out = format!(
+            "📊 {}\n{} — {}\n",
+            bold(m.digest_title),
+            self.from.format("%d.%m %H:%M"),
+            self.to.format("%d.%m %H:%M")
+        );
+
+        if self.warehouses.is_empty() {
+            out.push_str(&format!("\n{}\n", m.digest_no_slots));
+        }
+
+        for w in &self.warehouses {
+            let average = w.total_open / w.opened.max(1) as i32;
+            out.push_str(&format!(
+                "\n▫️ {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+                bold(&w.warehouse),
+                m.digest_slots_opened,
+                w.opened,
+                m.digest_open_average,
+                format_duration(average),
+                m.digest_open_longest,
+                format_duration(w.longest_open),
+                m.digest_min_coefficient,
+                w.min_coefficient
+            ));
+        }
+
+        if !self.unplanned.is_empty() {
+            out.push_str(&format!("\n⏳ {}\n", bold(m.digest_unplanned)));
+            for (preorder_id, warehouse) in &self.unplanned {
+                let url = util::preorder_id_to_url(*preorder_id);
+                let line = if html {
+                    format!(
+                        "• <a href=\"{}\">{}</a> ({preorder_id})\n",
+                        escape_html(&url),
+                        escape_html(warehouse)
+                    )
+                } else {
+                    format!("• {warehouse} ({preorder_id}): {url}\n")
+                };
+                out.push_str(&line);
+            }
+        }
+
+        out
+    }
+}
+
+/// Длительность в часах и минутах
+fn format_duration(d: TimeDelta) -> String {
+    let m = msg();
+    let minutes = d.num_minutes();
+    if minutes >= 60 {
+        format!(
+            "{} {} {} {}",
+            minutes / 60,
+            m.hours_short,
+            minutes % 60,
+            m.minutes_short
+        )
+    } else {
+        format!("{} {}", minutes, m.minutes_short)
+    }
+}
+
+/// Разбиение текста по строкам на части не длиннее `max` символов
+fn split_message(text: &str, max: usize) -> Vec<String> {
+    let mut parts = vec![String::new()];
+
+    for line in text.split_inclusive('\n') {
+        let last = parts.last_mut().unwrap();
+        if !last.is_empty() && last.chars().count() + line.chars().count() > max {
+            parts.push(String::new());
+        }
+        parts.last_mut().unwrap().push_str(line);
+    }
+
+    parts
+}
+
+/// Способ доставки сводки
+enum Delivery {
+    Telegram(Bot),
+    Email {
+        notifier: EmailNotifier,
+        subject: String,
+    },
+}
+
+impl Delivery {
+    async fn send(&self, report: &Report) -> std::result::Result<(), String> {
+        match self {
+            Self::Telegram(bot) => {
+                for part in split_message(&report.render(true), telebot::MESSAGE_MAX_LEN) {
+                    bot.write(part, None).await?;
+                }
+                Ok(())
+            }
+            Self::Email { notifier, subject } => {
+                let html = report.render(true).replace('\n', "<br>\n");
+                notifier
+                    .send(subject, report.render(false), html)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// Запуск отправки сводок по расписанию.
+/// Журнал слотов наполняется всеми обновлениями, без отключённых уведомлений и фильтров аккаунтов
+pub async fn start(cfg: &Config, trackers: &Arc<Trackers>) {
+    if cfg.digests.is_empty() {
+        return;
+    }
+
+    let log = Arc::new(SlotLog::default());
+    let mut updates = trackers.subscribe_all().await;
+    {
+        let log = Arc::clone(&log);
+        tokio::spawn(async move {
+            while let Some((account, updates)) = updates.recv().await {
+                log.record(&Event::new(account, updates)).await;
+            }
+        });
+    }
+
+    for digest in &cfg.digests {
+        let schedule = match digest.schedule.parse::<Schedule>() {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!(
+                    schedule = digest.schedule,
+                    error = %e,
+                    "{}",
+                    msg().digest_invalid_schedule
+                );
+                continue;
+            }
+        };
+
+        let delivery = match digest.via {
+            config::DigestVia::Telegram => {
+                let targets = cfg.telegram_bot.notification_targets();
+                if cfg.telegram_bot.token.is_empty() || targets.is_empty() {
+                    tracing::warn!("{}", msg().telegram_not_configured);
+                    continue;
+                }
+                Delivery::Telegram(
+                    BotBuilder::new(cfg.telegram_bot.token.expose())
+                        .add_targets(targets)
+                        .parse_mode("HTML")
+                        .build(),
+                )
+            }
+            config::DigestVia::Email => match EmailNotifier::new(&digest.email) {
+                Ok(notifier) => Delivery::Email {
+                    notifier,
+                    subject: if digest.email.subject.is_empty() {
+                        msg().digest_subject.to_string()
+                    } else {
+                        digest.email.subject.clone()
+                    },
+                },
+                Err(e) => {
+                    tracing::warn!(error = %e, "{}", msg().invalid_email_params);
+                    continue;
+                }
+            },
+        };
+
+        tokio::spawn(run(
+            schedule,
+            delivery,
+            Arc::clone(&log),
+            Arc::clone(trackers),
+        ));
+    }
+}
+
+/// Отправка сводок по расписанию. Каждая сводка охватывает время с предыдущей отправки
+async fn run(schedule: Schedule, delivery: Delivery, log: Arc<SlotLog>, trackers: Arc<Trackers>) {
+    let mut since = Local::now();
+
+    while let Some(next) = schedule.next_after(Local::now()) {
+        let wait = (next - Local::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        let now = Local::now();
+        let mut unplanned = Vec::new();
+        for t in trackers.iter() {
+            unplanned.extend(
+                t.supplies
+                    .read_supplies()
+                    .await
+                    .into_iter()
+                    .map(|(k, s)| (k, account_label(&t.name, &s.warehouse_name))),
+            );
+        }
+        let report = Report::new(since, now, log.stats(since, now).await, unplanned);
+
+        if let Err(e) = delivery.send(&report).await {
+            tracing::error!(error = %e, "{}", msg().digest_send_failed);
+        }
+        since = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tracking::SupplyUpdateAcceptanceCosts,
+        wbseller::models::{Cost, Supply},
+    };
+    use std::collections::HashMap;
+
+    fn event(time: DateTime<Local>, preorder_id: i64, opened: bool) -> Event {
+        let cost = Cost {
+            date: "2026-10-20T00:00:00Z".to_string(),
+            ..Default::default()
+        };
+        let update = SupplyUpdateAcceptanceCosts {
+            supply: Supply {
+                warehouse_name: "Коледино".to_string(),
+                ..Default::default()
+            },
+            costs: if opened {
+                vec![cost.clone()]
+            } else {
+                Vec::new()
+            },
+            closed_costs: if opened { Vec::new() } else { vec![cost] },
+        };
+        let mut event = Event::new(String::new(), HashMap::from([(preorder_id, update)]));
+        event.time = time;
+        event
+    }
+
+    #[tokio::test]
+    async fn expires_slots_by_opening_time() {
+        let log = SlotLog::default();
+        let start = Local::now() - TimeDelta::days(40);
+
+        // Не закрылся: поставку запланировали
+        log.record(&event(start, 1, true)).await;
+        log.record(&event(start, 2, true)).await;
+        log.record(&event(start + TimeDelta::hours(1), 2, false))
+            .await;
+        log.record(&event(start + TimeDelta::days(10), 3, true))
+            .await;
+        assert_eq!(log.slots.lock().await.len(), 3);
+
+        log.record(&event(Local::now(), 4, true)).await;
+        let slots = log.slots.lock().await;
+        assert_eq!(
+            slots.iter().map(|s| s.preorder_id).collect::<Vec<_>>(),
+            [3, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_open_time_within_period() {
+        let log = SlotLog::default();
+        let from = Local::now() - TimeDelta::hours(10);
+
+        log.record(&event(from + TimeDelta::hours(1), 1, true))
+            .await;
+        log.record(&event(from + TimeDelta::hours(3), 1, false))
+            .await;
+        log.record(&event(from + TimeDelta::hours(6), 2, true))
+            .await;
+
+        let to = from + TimeDelta::hours(10);
+        let stats = log.stats(from, to).await;
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].opened, 2);
+        assert_eq!(stats[0].longest_open, TimeDelta::hours(4));
+        assert_eq!(stats[0].total_open, TimeDelta::hours(6));
+    }
+}
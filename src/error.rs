@@ -1,4 +1,5 @@
 use crate::browser::BrowserError;
+use crate::i18n::msg;
 use crate::wbseller::error::WbSellerError;
 use std::io::Error as StdIoError;
 use std::result::Result as StdResult;
@@ -18,8 +19,66 @@ pub enum Error {
     #[error("WbSellerError: {0:?}")]
     WbSeller(#[from] WbSellerError),
 
-    #[error("TomlDeError: {0:?}")]
+    #[error("{m}: {0}", m = msg().login_failed)]
+    Login(WbSellerError),
+
+    #[error("{m}:\n{0}", m = msg().config_parse_failed)]
     TomlDe(#[from] TomlDeError),
+
+    #[error("{m} {0}: {1}", m = msg().config_read_failed)]
+    ConfigRead(String, StdIoError),
+
+    #[error("{m} {0}: {1}", m = msg().config_init_failed)]
+    ConfigWrite(String, StdIoError),
+
+    #[error("{m}:\n  - {list}", m = msg().config_invalid, list = .0.join("\n  - "))]
+    InvalidConfig(Vec<String>),
+
+    #[error("{m}: {0}", m = msg().unknown_account)]
+    UnknownAccount(String),
+
+    #[error("{m}: {0}", m = msg().log_init_failed)]
+    Logging(String),
+
+    #[error("{m}: {0:?}. {f}", m = msg().config_initialized_at, f = msg().config_fill_and_restart)]
+    ConfigCreated(std::path::PathBuf),
+
+    #[error("{m}", m = msg().account_required)]
+    AccountRequired,
+
+    #[error("{m} {0}", m = msg().browser_in_use)]
+    BrowserInUse(u16),
+
+    #[error("{m}", m = msg().interactive_required)]
+    InteractiveRequired,
+
+    #[error("{m} {0}: {1}", m = msg().pidfile_write_failed)]
+    Pidfile(String, StdIoError),
+
+    #[error("{0}: {1}")]
+    Signal(&'static str, StdIoError),
     // #[error("{0}")]
     // Custom(String),
 }
+
+impl Error {
+    /// Код завершения процесса (sysexits.h)
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            // EX_USAGE
+            Self::UnknownAccount(_) | Self::AccountRequired | Self::InteractiveRequired => 64,
+            // EX_CONFIG
+            Self::TomlDe(_) | Self::InvalidConfig(_) | Self::ConfigCreated(_) => 78,
+            // EX_CANTCREAT
+            Self::Pidfile(..) => 73,
+            // EX_TEMPFAIL
+            Self::BrowserInUse(_) => 75,
+            // EX_OSERR
+            Self::Signal(..) => 71,
+            // EX_IOERR
+            Self::ConfigRead(..) | Self::ConfigWrite(..) | Self::StdIo(_) | Self::Logging(_) => 74,
+            // EX_UNAVAILABLE
+            Self::Browser(_) | Self::WbSeller(_) | Self::Login(_) => 69,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use super::Messages;
+
+pub static MESSAGES: Messages = Messages {
+    listen_sigint_failed: "Failed to listen for SIGINT",
+    listen_sigterm_failed: "Failed to listen for SIGTERM",
+    listen_sighup_failed: "Failed to listen for SIGHUP",
+    shutdown_by_signal: "Shutting down on signal...",
+    interactive_required: "Login is not possible in non-interactive mode: run `wbsupplies login` in a terminal",
+    pidfile_write_failed: "Failed to write the PID file",
+    systemd_notify_failed: "Failed to notify systemd",
+    dead_letter_failed: "Failed to write the event to dead_letter_file",
+    shutdown_timeout: "Not all notifications were sent before shutdown",
+    config_init_failed: "Failed to initialize configuration",
+    config_initialized_at: "Configuration file initialized at",
+    config_fill_and_restart: "Fill in the configuration and start the program again",
+    login_alert: "Please sign in to your seller account",
+    login_waiting: "Waiting for sign-in to the seller account. Do not close the browser window",
+    login_account: "Account",
+    login_hint: "To sign in to the seller account, run: wbsupplies login",
+    unknown_account: "Account not found in the configuration",
+    account_required: "There are several accounts: choose one with --account",
+    browser_in_use: "The account browser is already running, probably by tracking (watch). Stop it or set another browser.port and user_data_dir. Port in use",
+    press_enter: "Press Enter to continue:",
+    login_success: "Signed in successfully!",
+    login_failed: "Failed to get authorization parameters for seller.wildberries.ru",
+    tracking_started: "Supply tracking started",
+    log_init_failed: "Failed to set up the log",
+    credentials_refreshed: "Credentials refreshed from the browser",
+    credentials_refresh_failed: "Failed to refresh credentials from the browser",
+    supplies_request_failed: "Supply list request failed",
+    costs_request_failed: "Supply acceptance costs request failed",
+    tracking_stalled: "Supply polls failed in a row",
+    tracking_recovered: "Supply polling recovered",
+    browser_connection_lost: "Connection to the browser lost",
+    browser_restarted: "Browser restarted after a lost connection",
+    browser_restart_failed: "Failed to restart the browser",
+    browser_launch_failed: "Failed to launch the browser, will retry",
+    tracking_restarted: "Account tracking crashed and will be restarted",
+
+    config_read_failed: "Failed to read configuration",
+    config_parse_failed: "Configuration format not recognized",
+    config_invalid: "Configuration errors",
+    config_empty: "must not be empty",
+    config_must_be_positive: "must be greater than 0",
+    config_invalid_value: "invalid value",
+    config_required_for_telegram: "is required for Telegram notifications",
+    config_no_recipients: "no notification recipients (targets and allow_users are empty)",
+    config_required_for_webhook: "is required when mode = \"webhook\"",
+    config_metrics_requires_server: "requires http_server.enabled = true",
+    config_path_slash: "must start with /",
+    config_tls_pair: "tls_cert and tls_key must be set together",
+    config_invalid_key: "invalid key",
+    config_unknown_key: "unknown setting",
+    config_index_out_of_range: "index out of range:",
+    config_reloaded: "Configuration reloaded",
+    config_reload_failed: "Failed to reload configuration, keeping the previous one",
+    config_restart_required: "Changes take effect after a restart",
+    config_migrated: "Configuration upgraded to version",
+    config_backup: "backup",
+    config_not_writable: "Could not add missing keys to the configuration file, using default values",
+    config_version_unsupported: "version is newer than this program supports",
+    invalid_default_config: "Invalid default configuration",
+    config_secret_sources: "set only one source",
+    config_duplicate: "duplicate value",
+    account_name_required: "set a name when there are several accounts",
+    config_env_not_set: "environment variable is not set",
+    secret_world_readable: "is readable by all users (chmod 600, or 700 for a directory, is recommended)",
+    config_check_ok: "Configuration is valid",
+    warning: "Warning",
+
+    telegram_not_configured: "Warning: Telegram bot token is missing or the recipient list is empty",
+    invalid_email_params: "Warning: invalid email parameters",
+    invalid_address: "Invalid address",
+    notify_failed: "Failed to send notification",
+    webhook_set_failed: "Failed to set webhook",
+    webhook_server_error: "Webhook server error",
+    supplies_update: "Supplies update",
+    open_supply: "Open supply",
+    email_subject: "WbSupplies: supplies update",
+
+    template_error: "Template error, using the default template",
+    invalid_default_template: "Invalid default template",
+    unclosed_tag: "unclosed tag {{",
+    unexpected_closing_tag: "unexpected closing tag",
+    mismatched_tags: "closing tag does not match the opening one",
+    unclosed_section: "unclosed section",
+    telegram_template: "🔊 <b><i>Supplies update</i></b>\n\
+        {{#account}}👤 <b>{{account}}</b>\n{{/account}}\n\
+        ▫️ <b>{{warehouse}}</b>\n\
+        {{#costs}}{{#closed}}<s>{{/closed}}\
+        Coefficient: <b>{{coefficient}}</b>\n\
+        Cost: <b>{{cost}}</b>\n\
+        Date: <b>{{short_date}}</b>\n\
+        {{#closed}}</s>{{/closed}}{{^last}}\n{{/last}}{{/costs}}",
+    email_template: "Supplies update\n\n\
+        {{#account}}Account: {{account}}\n\n{{/account}}\
+        {{#supplies}}▫️ {{warehouse}}\n\
+        {{#costs}}Coefficient: {{coefficient}}\n\
+        Cost: {{cost}}\n\
+        Date: {{short_date}}\n\n{{/costs}}\
+        {{url}}\n\n{{/supplies}}",
+    email_html_template: "<h3>Supplies update</h3>\
+        {{#account}}<p>Account: <b>{{account}}</b></p>{{/account}}\
+        {{#supplies}}<p><b><a href=\"{{url}}\">{{warehouse}}</a></b></p>\
+        <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+        <tr><th>Date</th><th>Coefficient</th><th>Cost</th></tr>\
+        {{#costs}}<tr><td>{{short_date}}</td><td>{{coefficient}}</td><td>{{cost}}</td></tr>{{/costs}}\
+        </table>{{/supplies}}",
+    desktop_template: "{{#costs}}{{short_date}} — x{{coefficient}}{{^last}}\n{{/last}}{{/costs}}",
+    slack_template: "*{{short_date}}* — coefficient *{{coefficient}}*, cost *{{cost}}*",
+    discord_template: "Coefficient: **{{coefficient}}**\nCost: **{{cost}}**",
+
+    cron_field_count: "expected 5 fields: minute hour day month weekday",
+    cron_invalid_value: "invalid value",
+    cron_invalid_step: "invalid step",
+    cron_invalid_range: "invalid range",
+    digest_invalid_schedule: "Invalid digest schedule",
+    digest_send_failed: "Failed to send digest",
+    chat_send_failed: "Failed to send a message to the chat",
+    chat_delete_failed: "Failed to delete a message in the chat",
+    digest_subject: "WbSupplies: slots digest",
+    digest_title: "Slots digest",
+    digest_no_slots: "No slots opened",
+    digest_slots_opened: "Slots opened",
+    digest_open_average: "Open on average",
+    digest_open_longest: "Longest open",
+    digest_min_coefficient: "Cheapest coefficient",
+    digest_unplanned: "Still unplanned",
+    hours_short: "h",
+    minutes_short: "min",
+
+    invalid_http_server_listen: "Invalid http_server.listen address",
+    http_server_started: "HTTP server started",
+    http_server_error: "HTTP server error",
+    page_title: "Acceptance slots",
+    page_supply: "Supply",
+    page_warehouse: "Warehouse",
+    page_last_poll: "Last poll",
+    page_no_supplies: "No tracked supplies",
+
+    mute_warehouse_button: "🔕 Warehouse for 24 h",
+    mute_supply_button: "🔕 Supply",
+    all_dates_button: "📅 All dates",
+    access_denied: "Access denied",
+    warehouse_muted: "Warehouse notifications muted for 24 h",
+    supply_muted: "Supply notifications muted",
+    no_available_dates: "📅 No available dates",
+    available_dates: "📅 <b><i>Available dates</i></b>",
+    coefficient: "coefficient",
+    cost: "cost",
+    request_failed: "Request failed",
+    status_error: "Error: status",
+    invalid_mime: "Invalid MIME type",
+};
@@ -0,0 +1,199 @@
+//! Локализация сообщений
+
+mod en;
+mod ru;
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Язык сообщений
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Ru,
+    En,
+}
+
+impl Language {
+    /// Определение языка по переменным окружения `LC_ALL`, `LC_MESSAGES` и `LANG`
+    pub fn detect() -> Self {
+        let lang = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|k| std::env::var(k).ok())
+            .find(|v| !v.is_empty())
+            .unwrap_or_default();
+
+        if lang.starts_with("en") {
+            Self::En
+        } else {
+            Self::Ru
+        }
+    }
+
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Self::Ru => &ru::MESSAGES,
+            Self::En => &en::MESSAGES,
+        }
+    }
+}
+
+/// Установка языка сообщений. Повторный вызов игнорируется
+pub fn set_language(lang: Language) {
+    let _ = LANGUAGE.set(lang);
+}
+
+/// Текущий язык. До загрузки конфигурации определяется по окружению
+pub fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_else(Language::detect)
+}
+
+/// Каталог сообщений текущего языка
+pub fn msg() -> &'static Messages {
+    language().messages()
+}
+
+/// Каталог сообщений
+pub struct Messages {
+    // Запуск и завершение
+    pub listen_sigint_failed: &'static str,
+    pub listen_sigterm_failed: &'static str,
+    pub listen_sighup_failed: &'static str,
+    pub shutdown_by_signal: &'static str,
+    pub interactive_required: &'static str,
+    pub pidfile_write_failed: &'static str,
+    pub systemd_notify_failed: &'static str,
+    pub dead_letter_failed: &'static str,
+    pub shutdown_timeout: &'static str,
+    pub config_init_failed: &'static str,
+    pub config_initialized_at: &'static str,
+    pub config_fill_and_restart: &'static str,
+    pub login_alert: &'static str,
+    pub login_waiting: &'static str,
+    pub login_account: &'static str,
+    pub login_hint: &'static str,
+    pub unknown_account: &'static str,
+    pub account_required: &'static str,
+    pub browser_in_use: &'static str,
+    pub press_enter: &'static str,
+    pub login_success: &'static str,
+    pub login_failed: &'static str,
+    pub tracking_started: &'static str,
+    pub log_init_failed: &'static str,
+    pub credentials_refreshed: &'static str,
+    pub credentials_refresh_failed: &'static str,
+    pub supplies_request_failed: &'static str,
+    pub costs_request_failed: &'static str,
+    pub tracking_stalled: &'static str,
+    pub tracking_recovered: &'static str,
+    pub browser_connection_lost: &'static str,
+    pub browser_restarted: &'static str,
+    pub browser_restart_failed: &'static str,
+    pub browser_launch_failed: &'static str,
+    pub tracking_restarted: &'static str,
+
+    // Конфигурация
+    pub config_read_failed: &'static str,
+    pub config_parse_failed: &'static str,
+    pub config_invalid: &'static str,
+    pub config_empty: &'static str,
+    pub config_must_be_positive: &'static str,
+    pub config_invalid_value: &'static str,
+    pub config_required_for_telegram: &'static str,
+    pub config_no_recipients: &'static str,
+    pub config_required_for_webhook: &'static str,
+    pub config_metrics_requires_server: &'static str,
+    pub config_path_slash: &'static str,
+    pub config_tls_pair: &'static str,
+    pub config_invalid_key: &'static str,
+    pub config_unknown_key: &'static str,
+    pub config_index_out_of_range: &'static str,
+    pub config_reloaded: &'static str,
+    pub config_reload_failed: &'static str,
+    pub config_restart_required: &'static str,
+    pub config_migrated: &'static str,
+    pub config_backup: &'static str,
+    pub config_not_writable: &'static str,
+    pub config_version_unsupported: &'static str,
+    pub invalid_default_config: &'static str,
+    pub config_secret_sources: &'static str,
+    pub config_duplicate: &'static str,
+    pub account_name_required: &'static str,
+    pub config_env_not_set: &'static str,
+    pub secret_world_readable: &'static str,
+    pub config_check_ok: &'static str,
+    pub warning: &'static str,
+
+    // Получатели уведомлений
+    pub telegram_not_configured: &'static str,
+    pub invalid_email_params: &'static str,
+    pub invalid_address: &'static str,
+    pub notify_failed: &'static str,
+    pub webhook_set_failed: &'static str,
+    pub webhook_server_error: &'static str,
+    pub supplies_update: &'static str,
+    pub open_supply: &'static str,
+    pub email_subject: &'static str,
+
+    // Шаблоны
+    pub template_error: &'static str,
+    pub invalid_default_template: &'static str,
+    pub unclosed_tag: &'static str,
+    pub unexpected_closing_tag: &'static str,
+    pub mismatched_tags: &'static str,
+    pub unclosed_section: &'static str,
+    pub telegram_template: &'static str,
+    pub email_template: &'static str,
+    pub email_html_template: &'static str,
+    pub desktop_template: &'static str,
+    pub slack_template: &'static str,
+    pub discord_template: &'static str,
+
+    // Сводки
+    pub cron_field_count: &'static str,
+    pub cron_invalid_value: &'static str,
+    pub cron_invalid_step: &'static str,
+    pub cron_invalid_range: &'static str,
+    pub digest_invalid_schedule: &'static str,
+    pub digest_send_failed: &'static str,
+    pub chat_send_failed: &'static str,
+    pub chat_delete_failed: &'static str,
+    pub digest_subject: &'static str,
+    pub digest_title: &'static str,
+    pub digest_no_slots: &'static str,
+    pub digest_slots_opened: &'static str,
+    pub digest_open_average: &'static str,
+    pub digest_open_longest: &'static str,
+    pub digest_min_coefficient: &'static str,
+    pub digest_unplanned: &'static str,
+    pub hours_short: &'static str,
+    pub minutes_short: &'static str,
+
+    // HTTP-сервер
+    pub invalid_http_server_listen: &'static str,
+    pub http_server_started: &'static str,
+    pub http_server_error: &'static str,
+    pub page_title: &'static str,
+    pub page_supply: &'static str,
+    pub page_warehouse: &'static str,
+    pub page_last_poll: &'static str,
+    pub page_no_supplies: &'static str,
+
+    // Телеграм-бот
+    pub mute_warehouse_button: &'static str,
+    pub mute_supply_button: &'static str,
+    pub all_dates_button: &'static str,
+    pub access_denied: &'static str,
+    pub warehouse_muted: &'static str,
+    pub supply_muted: &'static str,
+    pub no_available_dates: &'static str,
+    pub available_dates: &'static str,
+    pub coefficient: &'static str,
+    pub cost: &'static str,
+    pub request_failed: &'static str,
+    pub status_error: &'static str,
+    pub invalid_mime: &'static str,
+}
@@ -0,0 +1,154 @@
+use super::Messages;
+
+pub static MESSAGES: Messages = Messages {
+    listen_sigint_failed: "Не удалось слушать SIGINT",
+    listen_sigterm_failed: "Не удалось слушать SIGTERM",
+    listen_sighup_failed: "Не удалось слушать SIGHUP",
+    shutdown_by_signal: "Завершение по сигналу...",
+    interactive_required: "Вход в личный кабинет невозможен в неинтерактивном режиме: выполните `wbsupplies login` в терминале",
+    pidfile_write_failed: "Не удалось записать файл PID",
+    systemd_notify_failed: "Не удалось отправить уведомление systemd",
+    dead_letter_failed: "Не удалось записать событие в dead_letter_file",
+    shutdown_timeout: "Не все уведомления отправлены до завершения",
+    config_init_failed: "Ошибка инициализации конфигурации",
+    config_initialized_at: "Файл конфигурации инициализирован по пути",
+    config_fill_and_restart: "Заполните конфигурацию и запустите программу снова",
+    login_alert: "Выполните вход в личный кабинет",
+    login_waiting: "Ожидание входа в личный кабинет. Не закрывайте окно браузера",
+    login_account: "Аккаунт",
+    login_hint: "Для входа в личный кабинет выполните: wbsupplies login",
+    unknown_account: "Аккаунт не найден в конфигурации",
+    account_required: "Аккаунтов несколько: укажите нужный параметром --account",
+    browser_in_use: "Браузер аккаунта уже запущен, вероятно, отслеживанием (watch). Остановите его или укажите другие browser.port и user_data_dir. Занят порт",
+    press_enter: "Нажмите Enter чтобы продолжить:",
+    login_success: "Авторизация прошла успешно!",
+    login_failed: "Не удалось получить параметры авторизации личного кабинета seller.wildberries.ru",
+    tracking_started: "Процесс отслеживания поставок запущен",
+    log_init_failed: "Не удалось настроить журнал",
+    credentials_refreshed: "Учётные данные обновлены из браузера",
+    credentials_refresh_failed: "Не удалось обновить учётные данные из браузера",
+    supplies_request_failed: "Ошибка запроса списка поставок",
+    costs_request_failed: "Ошибка запроса себестоимости поставки",
+    tracking_stalled: "Подряд не удались опросы поставок",
+    tracking_recovered: "Опрос поставок восстановлен",
+    browser_connection_lost: "Соединение с браузером потеряно",
+    browser_restarted: "Браузер перезапущен после потери соединения",
+    browser_restart_failed: "Не удалось перезапустить браузер",
+    browser_launch_failed: "Не удалось запустить браузер, попытка будет повторена",
+    tracking_restarted: "Отслеживание аккаунта аварийно завершилось и будет перезапущено",
+
+    config_read_failed: "Ошибка чтения конфигурации",
+    config_parse_failed: "Формат конфигурации не распознан",
+    config_invalid: "Ошибки в конфигурации",
+    config_empty: "не может быть пустым",
+    config_must_be_positive: "должно быть больше 0",
+    config_invalid_value: "некорректное значение",
+    config_required_for_telegram: "обязателен для уведомлений в Telegram",
+    config_no_recipients: "нет получателей уведомлений (targets и allow_users пусты)",
+    config_required_for_webhook: "обязателен при mode = \"webhook\"",
+    config_metrics_requires_server: "требует http_server.enabled = true",
+    config_path_slash: "должен начинаться с /",
+    config_tls_pair: "tls_cert и tls_key указываются вместе",
+    config_invalid_key: "некорректный ключ",
+    config_unknown_key: "неизвестный параметр",
+    config_index_out_of_range: "индекс за пределами списка:",
+    config_reloaded: "Конфигурация перезагружена",
+    config_reload_failed: "Не удалось перезагрузить конфигурацию, действует прежняя",
+    config_restart_required: "Изменения вступят в силу после перезапуска",
+    config_migrated: "Конфигурация обновлена до версии",
+    config_backup: "резервная копия",
+    config_not_writable: "Не удалось дописать недостающие параметры в файл конфигурации, используются значения по умолчанию",
+    config_version_unsupported: "версия новее поддерживаемой программой",
+    invalid_default_config: "Некорректная конфигурация по умолчанию",
+    config_secret_sources: "задайте только один источник",
+    config_duplicate: "значение повторяется",
+    account_name_required: "укажите название, если аккаунтов несколько",
+    config_env_not_set: "не задана переменная окружения",
+    secret_world_readable: "доступен для чтения всем пользователям (рекомендуется chmod 600 или 700 для папки)",
+    config_check_ok: "Конфигурация корректна",
+    warning: "Предупреждение",
+
+    telegram_not_configured: "Предупреждение: не указан токен телеграм-бота или пуст список получателей",
+    invalid_email_params: "Предупреждение: некорректные параметры email",
+    invalid_address: "Некорректный адрес",
+    notify_failed: "Ошибка отправки уведомления",
+    webhook_set_failed: "Не удалось установить webhook",
+    webhook_server_error: "Ошибка сервера webhook",
+    supplies_update: "Обновление поставок",
+    open_supply: "Открыть поставку",
+    email_subject: "WbSupplies: обновление поставок",
+
+    template_error: "Ошибка шаблона, используется шаблон по умолчанию",
+    invalid_default_template: "Некорректный шаблон по умолчанию",
+    unclosed_tag: "незакрытый тег {{",
+    unexpected_closing_tag: "лишний закрывающий тег",
+    mismatched_tags: "закрывающий тег не соответствует открывающему",
+    unclosed_section: "не закрыта секция",
+    telegram_template: "🔊 <b><i>Обновление поставок</i></b>\n\
+        {{#account}}👤 <b>{{account}}</b>\n{{/account}}\n\
+        ▫️ <b>{{warehouse}}</b>\n\
+        {{#costs}}{{#closed}}<s>{{/closed}}\
+        Коэффициент: <b>{{coefficient}}</b>\n\
+        Стоимость: <b>{{cost}}</b>\n\
+        Дата: <b>{{short_date}}</b>\n\
+        {{#closed}}</s>{{/closed}}{{^last}}\n{{/last}}{{/costs}}",
+    email_template: "Обновление поставок\n\n\
+        {{#account}}Аккаунт: {{account}}\n\n{{/account}}\
+        {{#supplies}}▫️ {{warehouse}}\n\
+        {{#costs}}Коэффициент: {{coefficient}}\n\
+        Стоимость: {{cost}}\n\
+        Дата: {{short_date}}\n\n{{/costs}}\
+        {{url}}\n\n{{/supplies}}",
+    email_html_template: "<h3>Обновление поставок</h3>\
+        {{#account}}<p>Аккаунт: <b>{{account}}</b></p>{{/account}}\
+        {{#supplies}}<p><b><a href=\"{{url}}\">{{warehouse}}</a></b></p>\
+        <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+        <tr><th>Дата</th><th>Коэффициент</th><th>Стоимость</th></tr>\
+        {{#costs}}<tr><td>{{short_date}}</td><td>{{coefficient}}</td><td>{{cost}}</td></tr>{{/costs}}\
+        </table>{{/supplies}}",
+    desktop_template: "{{#costs}}{{short_date}} — x{{coefficient}}{{^last}}\n{{/last}}{{/costs}}",
+    slack_template: "*{{short_date}}* — коэффициент *{{coefficient}}*, стоимость *{{cost}}*",
+    discord_template: "Коэффициент: **{{coefficient}}**\nСтоимость: **{{cost}}**",
+
+    cron_field_count: "ожидается 5 полей: минута час день месяц день_недели",
+    cron_invalid_value: "некорректное значение",
+    cron_invalid_step: "некорректный шаг",
+    cron_invalid_range: "некорректный диапазон",
+    digest_invalid_schedule: "Некорректное расписание сводки",
+    digest_send_failed: "Ошибка отправки сводки",
+    chat_send_failed: "Не удалось отправить сообщение в чат",
+    chat_delete_failed: "Не удалось удалить сообщение в чате",
+    digest_subject: "WbSupplies: сводка по слотам",
+    digest_title: "Сводка по слотам",
+    digest_no_slots: "Новых слотов не было",
+    digest_slots_opened: "Открылось слотов",
+    digest_open_average: "Доступны в среднем",
+    digest_open_longest: "Дольше всего",
+    digest_min_coefficient: "Минимальный коэффициент",
+    digest_unplanned: "Не запланированы",
+    hours_short: "ч",
+    minutes_short: "мин",
+
+    invalid_http_server_listen: "Некорректный адрес http_server.listen",
+    http_server_started: "HTTP-сервер запущен",
+    http_server_error: "Ошибка HTTP-сервера",
+    page_title: "Слоты приёмки",
+    page_supply: "Поставка",
+    page_warehouse: "Склад",
+    page_last_poll: "Последний опрос",
+    page_no_supplies: "Нет отслеживаемых поставок",
+
+    mute_warehouse_button: "🔕 Склад на 24 ч",
+    mute_supply_button: "🔕 Поставка",
+    all_dates_button: "📅 Все даты",
+    access_denied: "Нет доступа",
+    warehouse_muted: "Уведомления по складу отключены на 24 ч",
+    supply_muted: "Уведомления по поставке отключены",
+    no_available_dates: "📅 Доступных дат нет",
+    available_dates: "📅 <b><i>Доступные даты</i></b>",
+    coefficient: "коэффициент",
+    cost: "стоимость",
+    request_failed: "Ошибка при запросе",
+    status_error: "Ошибка: статус",
+    invalid_mime: "Некорректный MIME-тип",
+};
@@ -0,0 +1,76 @@
+//! Журнал работы: вывод в stderr и в файлы с ротацией
+
+use std::io::IsTerminal;
+use tracing::Subscriber;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan,
+    util::SubscriberInitExt,
+};
+
+use crate::{
+    config::{LogFormat, LogRotation, Logging},
+    error::{Error, Result},
+};
+
+/// Инициализация журнала. Возвращаемое значение нужно хранить до завершения программы,
+/// иначе последние записи в файл могут быть потеряны
+pub fn init(cfg: &Logging) -> Result<Option<WorkerGuard>> {
+    let filter = EnvFilter::try_new(cfg.filter()).map_err(|e| Error::Logging(e.to_string()))?;
+
+    // Без терминала (systemd, перенаправление в файл) цвета не нужны
+    let ansi = std::io::stderr().is_terminal();
+    let mut layers = vec![layer(cfg.format, std::io::stderr, ansi)];
+    let guard = if cfg.file {
+        // Без папки очистка старых файлов выводит ошибку при запуске
+        let dir = cfg.dir_path();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| Error::Logging(format!("{}: {e}", dir.display())))?;
+
+        let mut builder = RollingFileAppender::builder()
+            .rotation(match cfg.rotation {
+                LogRotation::Hourly => Rotation::HOURLY,
+                LogRotation::Daily => Rotation::DAILY,
+                LogRotation::Never => Rotation::NEVER,
+            })
+            .filename_prefix(env!("CARGO_PKG_NAME").to_lowercase())
+            .filename_suffix("log");
+        if cfg.max_files > 0 {
+            builder = builder.max_log_files(cfg.max_files);
+        }
+        let appender = builder
+            .build(&dir)
+            .map_err(|e| Error::Logging(format!("{}: {e}", dir.display())))?;
+
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        layers.push(layer(cfg.format, writer, false));
+        Some(guard)
+    } else {
+        None
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .try_init()
+        .map_err(|e| Error::Logging(e.to_string()))?;
+
+    Ok(guard)
+}
+
+fn layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
@@ -1,15 +1,26 @@
 mod browser;
+mod cli;
+mod commands;
 mod config;
+mod daemon;
+mod digest;
 mod error;
+mod i18n;
+mod logging;
+mod metrics;
+mod notify;
+mod server;
 mod telebot;
 mod tracking;
 mod util;
 mod wbseller;
 
-use browser::*;
+use clap::Parser;
 use error::Result;
+use i18n::msg;
+use std::{path::Path, process::ExitCode, sync::Arc, time::Duration};
 
-use crate::{config::Config, tracking::TrackingSupplies};
+use crate::{config::Config, tracking::Trackers};
 
 const LOGO: &str = r#"
 
@@ -21,187 +32,246 @@ const LOGO: &str = r#"
        \/       \/        \/      |__|   |__|                \/     \/
 "#;
 
-/// Обработка сигнала завершения (Ctrl+C или SIGINT/SIGTERM)
-async fn shutdown_signal() {
-    #[cfg(windows)]
-    {
-        let _ = tokio::signal::ctrl_c().await;
-    }
+/// Сколько ждать отправки уже полученных обновлений при завершении
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Обработка сигнала завершения (Ctrl+C или SIGINT/SIGTERM).
+/// Обработчики устанавливаются при вызове, ошибка установки возвращается сразу
+fn shutdown_signal() -> Result<impl Future<Output = ()>> {
     #[cfg(unix)]
-    {
+    let (mut sigint, mut sigterm) = {
         use tokio::signal::unix::{SignalKind, signal};
-        let mut sigint = signal(SignalKind::interrupt()).expect("Не удалось слушать SIGINT");
-        let mut sigterm = signal(SignalKind::terminate()).expect("Не удалось слушать SIGTERM");
+        (
+            signal(SignalKind::interrupt())
+                .map_err(|e| error::Error::Signal(msg().listen_sigint_failed, e))?,
+            signal(SignalKind::terminate())
+                .map_err(|e| error::Error::Signal(msg().listen_sigterm_failed, e))?,
+        )
+    };
 
-        tokio::select! {
-            _ = sigint.recv() => {},
-            _ = sigterm.recv() => {},
+    Ok(async move {
+        #[cfg(windows)]
+        {
+            let _ = tokio::signal::ctrl_c().await;
         }
-    }
 
-    println!("Завершение по сигналу...");
+        #[cfg(unix)]
+        {
+            tokio::select! {
+                _ = sigint.recv() => {},
+                _ = sigterm.recv() => {},
+            }
+        }
+
+        tracing::info!("{}", msg().shutdown_by_signal);
+    })
 }
 
-/// Инициализация конфигурации и авторизация пользователя
-async fn startup() -> Result<&'static Config> {
-    // Если конфигурация не инициализирована — инициализируем
-    if config::init_if_not().expect("Ошибка инициализации конфигурации")
-    {
-        let full_config_path = std::env::current_dir()?.join(config::CONFIG_PATH);
-        println!(
-            "Файл конфигурации инициализирован по пути: {:?}",
-            full_config_path
-        );
-        let _ = open::that_in_background(full_config_path);
-    }
+/// Перезагрузка конфигурации по сигналу SIGHUP
+fn reload_signal() -> Result<impl Future<Output = ()>> {
+    #[cfg(unix)]
+    let mut sighup = {
+        use tokio::signal::unix::{SignalKind, signal};
+        signal(SignalKind::hangup())
+            .map_err(|e| error::Error::Signal(msg().listen_sighup_failed, e))?
+    };
 
-    let cfg = config::get();
+    Ok(async move {
+        #[cfg(windows)]
+        {
+            std::future::pending::<()>().await;
+        }
 
-    // Первая загрузка: открываем браузер для авторизации
-    if cfg.launch_options.first_run {
-        let mut browser_config = cfg.browser.clone();
-        browser_config.headless = false;
-        let bs_config = browser_config.to_browser_session_config();
-        let mut bs = BrowserSession::launch(&bs_config).await?;
-        let page = bs.open("https://seller.wildberries.ru").await?;
-
-        // Показываем alert для входа
-        tokio::spawn(async move {
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-            let _ = page
-                .evaluate(r#"alert("Выполните вход в личный кабинет")"#)
-                .await;
-        });
-
-        println!("Ожидание входа в личный кабинет. Не закрывайте окно браузера");
-        println!("Нажмите Enter чтобы продолжить:");
-        let _ = std::io::stdin().read_line(&mut String::new());
-
-        // Проверяем успешность авторизации
-        if wbseller::Client::from_browser_session(&bs).await.is_ok() {
-            println!("Авторизация прошла успешно!");
-        } else {
-            panic!(
-                "Не удалось получить параметры авторизации личного кабинета seller.wildberries.ru"
-            );
+        #[cfg(unix)]
+        {
+            while sighup.recv().await.is_some() {
+                config::reload_and_report().await;
+            }
         }
+    })
+}
 
-        bs.close().await;
+/// Инициализация и обновление файла конфигурации.
+/// Возвращает `None`, если файл конфигурации только что создан и его нужно заполнить.
+/// В неинтерактивном режиме это ошибка
+async fn startup(interactive: bool) -> Result<Option<Arc<Config>>> {
+    // Если конфигурация не инициализирована — инициализируем
+    if config::init_if_not()? {
+        let full_config_path = std::env::current_dir()?.join(&config::sources().path);
+        if !interactive {
+            return Err(error::Error::ConfigCreated(full_config_path));
+        }
+        eprintln!("{}: {:?}", msg().config_initialized_at, full_config_path);
+        eprintln!("{}", msg().config_fill_and_restart);
+        eprintln!("{}", msg().login_hint);
+        let _ = open::that_in_background(full_config_path);
+        return Ok(None);
     }
 
-    // Предупреждение о некорректной настройке телеграм-бота
-    if cfg.launch_options.telegram_notifications
-        && (cfg.telegram_bot.token.is_empty() || cfg.telegram_bot.allow_users.is_empty())
-    {
-        println!("Предупреждение: не указан токен телеграм-бота или пуст список пользователей");
+    // Обновление файла конфигурации, созданного предыдущей версией программы
+    match config::migrate()? {
+        Some(config::Migration::Updated(backup)) => eprintln!(
+            "{} {}, {}: {:?}",
+            msg().config_migrated,
+            config::CONFIG_VERSION,
+            msg().config_backup,
+            backup
+        ),
+        Some(config::Migration::NotWritable(e)) => {
+            eprintln!("{}: {}: {e}", msg().warning, msg().config_not_writable)
+        }
+        None => {}
     }
 
-    Ok(cfg)
+    config::get().map(Some)
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    println!("\x1b[95m{}\x1b[0m\n", LOGO);
-    let cfg = startup().await?;
-
-    // Инициализация телеграм-бота (если включён)
-    let bot = if cfg.launch_options.telegram_notifications
-        && !cfg.telegram_bot.token.is_empty()
-        && !cfg.telegram_bot.allow_users.is_empty()
-    {
-        let token = cfg.telegram_bot.token.clone();
-        let allow_users = cfg
-            .telegram_bot
-            .allow_users
-            .iter()
-            .map(ToString::to_string)
-            .collect::<Vec<_>>();
-        Some(
-            telebot::BotBuilder::new(token)
-                .add_chat_ids(allow_users)
-                .parse_mode("HTML")
-                .build(),
-        )
-    } else {
-        None
-    };
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
 
-    // Запуск браузерной сессии и слежение за поставками
-    let bs_config = cfg.browser.to_browser_session_config();
-    let tracking_supplies = TrackingSupplies::watch(&bs_config).await?;
-    let mut rx = tracking_supplies.subscribe_channel().await;
+async fn run() -> Result<()> {
+    let cli = cli::Cli::parse();
+    config::init_sources(cli.sources());
 
-    let shutdown_handle = tokio::spawn({
-        async move {
-            shutdown_signal().await;
-            tracking_supplies.close().await;
-        }
-    });
+    let command = cli.command.unwrap_or(cli::Command::Watch);
+    if let cli::Command::Config {
+        action: cli::ConfigAction::Check,
+    } = command
+    {
+        return commands::check_config();
+    }
 
-    println!("Процесс отслеживания поставок запущен");
+    let interactive = !cli.non_interactive;
 
-    while rx.changed().await.is_ok() {
-        let data = rx.borrow_and_update();
-        if data.is_err() {
-            break;
-        }
-        if data.as_ref().unwrap().is_none() {
-            continue;
+    // Вывод list, costs и export может разбираться другими программами
+    if interactive && matches!(command, cli::Command::Login { .. } | cli::Command::Watch) {
+        eprintln!("\x1b[95m{}\x1b[0m\n", LOGO);
+    }
+
+    let Some(cfg) = startup(interactive).await? else {
+        return Ok(());
+    };
+    let _log_guard = logging::init(&cfg.logging)?;
+
+    match command {
+        cli::Command::Login { .. } if !interactive => Err(error::Error::InteractiveRequired),
+        cli::Command::Login { account } => commands::login(&cfg, account.as_deref()).await,
+        cli::Command::Watch => watch(cfg, interactive, cli.pidfile.as_deref()).await,
+        cli::Command::List { status, output } => commands::list(&cfg, status, &output).await,
+        cli::Command::Costs {
+            preorder_id,
+            days,
+            output,
+        } => commands::costs(&cfg, preorder_id, days, &output).await,
+        cli::Command::Export { days, file, output } => {
+            commands::export(&cfg, days, file.as_deref(), &output).await
         }
-        let data = data.clone().unwrap().unwrap();
+        // Обработана до загрузки конфигурации
+        cli::Command::Config { .. } => Ok(()),
+    }
+}
 
-        if cfg.launch_options.verbose {
-            println!("Обновление поставок: {:#?}", data);
+/// Отслеживание поставок всех аккаунтов и отправка уведомлений
+async fn watch(cfg: Arc<Config>, interactive: bool, pidfile: Option<&Path>) -> Result<()> {
+    // Устаревший способ входа: first_run = true в конфигурации
+    if cfg.launch_options.first_run {
+        if !interactive {
+            return Err(error::Error::InteractiveRequired);
         }
+        commands::login(&cfg, None).await?;
+    }
+
+    let _pidfile = pidfile
+        .map(|p| daemon::Pidfile::create(&config::data_path(p)))
+        .transpose()?;
+
+    // Запуск браузерных сессий и слежение за поставками всех аккаунтов
+    let trackers = Arc::new(Trackers::watch(cfg.accounts().into_iter().map(|a| {
+        let bs_config = a.browser(&cfg.browser).to_browser_session_config();
+        let options = a.to_tracking_options(&cfg.tracking_supplies);
+        (a.name, bs_config, options)
+    })));
+    let mut rx = trackers.subscribe().await;
+
+    // Получатели уведомлений
+    let mut telegram = None;
+    let mut notifiers = notify::from_config(&cfg, &trackers, &mut telegram).await;
+
+    // Периодические сводки
+    digest::start(&cfg, &trackers).await;
+
+    // HTTP-сервер: события передаются подписчикам /events
+    let event_stream = server::start(&cfg, &trackers)?;
+    if let Some(event_stream) = &event_stream {
+        notifiers.push(Box::new(event_stream.clone()));
+    }
+
+    // Перезагрузка конфигурации при изменении файла или по SIGHUP
+    let mut config_rx = config::subscribe()?;
+    tokio::spawn(config::watch_file());
+    tokio::spawn(reload_signal()?);
+
+    tracing::info!("{}", msg().tracking_started);
+    daemon::notify_ready(&trackers);
 
-        let urls: Vec<_> = data.keys().map(|k| util::preorder_id_to_url(*k)).collect();
+    let shutdown = shutdown_signal()?;
+    tokio::pin!(shutdown);
 
-        // Открытие ссылок в браузере, если включено
-        if cfg.launch_options.open {
-            for url in &urls {
-                open::with_in_background(url, "chrome");
+    // Уведомление отправляется целиком: сигнал завершения обрабатывается между событиями
+    loop {
+        tokio::select! {
+            () = &mut shutdown => break,
+            update = rx.recv() => {
+                let Some((account, updates)) = update else {
+                    break;
+                };
+                let event = notify::Event::new(account, updates);
+                notify::notify_all(&notifiers, &event).await;
+            }
+            Ok(()) = config_rx.changed() => {
+                let cfg = Arc::clone(&config_rx.borrow_and_update());
+                // Новые аккаунты запускаются только после перезапуска
+                for account in cfg.accounts() {
+                    if let Some(tracking_supplies) = trackers.get(&account.name) {
+                        tracking_supplies
+                            .set_options(account.to_tracking_options(&cfg.tracking_supplies))
+                            .await;
+                    }
+                }
+
+                notifiers = notify::from_config(&cfg, &trackers, &mut telegram).await;
+                if let Some(event_stream) = &event_stream {
+                    notifiers.push(Box::new(event_stream.clone()));
+                }
             }
         }
+    }
 
-        // Отправка уведомления через телеграм-бота
-        if let Some(ref b) = bot {
-            let mut message = String::from("🔊 <b><i>Обновление поставок</i></b>\n\n");
-            let mut reply_markup = Vec::with_capacity(urls.len());
-
-            for ((url, v), warehouse) in urls
-                .iter()
-                .zip(data.values())
-                .zip(data.values().map(|v| &v.supply.warehouse_name))
-            {
-                message.push_str(&format!("▫️ <b>{}</b>\n", warehouse));
-
-                let costs_info = v
-                    .costs
-                    .iter()
-                    .map(|c| {
-                        let short_date = c.date.split_once('T').map_or(&c.date[..], |(d, _)| d);
-                        format!(
-                            "Коэффициент: <b>{}</b>\nСтоимость: <b>{}</b>\nДата: <b>{}</b>\n",
-                            c.coefficient, c.cost, short_date
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-
-                message.push_str(&costs_info);
-                message.push_str("\n\n");
-
-                reply_markup.push(vec![telebot::types::InlineKeyboardMarkup {
-                    text: warehouse.clone(),
-                    url: url.to_string(),
-                }]);
-            }
+    daemon::notify_stopping();
 
-            let _ = b.write(&message, Some(&reply_markup)).await;
+    // Обновления, полученные до сигнала, отправляются до закрытия браузеров
+    rx.close();
+    let pending = async {
+        while let Some((account, updates)) = rx.recv().await {
+            let event = notify::Event::new(account, updates);
+            notify::notify_all(&notifiers, &event).await;
         }
+    };
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, pending)
+        .await
+        .is_err()
+    {
+        tracing::warn!("{}", msg().shutdown_timeout);
     }
 
-    let _ = shutdown_handle.await;
+    trackers.close().await;
     Ok(())
 }
@@ -0,0 +1,149 @@
+//! Метрики в формате Prometheus
+
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::sync::LazyLock;
+
+/// Префикс названий метрик
+const NAMESPACE: &str = "wbsupplies";
+
+/// Метрики программы. Заполняются отслеживанием поставок и получателями уведомлений
+pub struct Metrics {
+    registry: Registry,
+    pub polls: IntCounterVec,
+    pub poll_failures: IntCounterVec,
+    pub poll_duration: HistogramVec,
+    pub tracked_supplies: IntGaugeVec,
+    pub open_slots: IntGaugeVec,
+    pub min_coefficient: GaugeVec,
+    pub notifications: IntCounterVec,
+    pub notification_failures: IntCounterVec,
+    pub credential_refreshes: IntCounterVec,
+    pub credential_refresh_failures: IntCounterVec,
+    pub browser_restarts: IntCounterVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Метрики программы
+pub fn get() -> &'static Metrics {
+    &METRICS
+}
+
+fn opts(name: &str, help: &str) -> Opts {
+    Opts::new(name, help).namespace(NAMESPACE)
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        // Названия и метки заданы в коде, ошибка регистрации возможна только при повторе названия
+        fn register<T: prometheus::core::Collector + Clone + 'static>(
+            registry: &Registry,
+            metric: prometheus::Result<T>,
+        ) -> T {
+            let metric = metric.unwrap_or_else(|e| panic!("metrics: {e}"));
+            registry
+                .register(Box::new(metric.clone()))
+                .unwrap_or_else(|e| panic!("metrics: {e}"));
+            metric
+        }
+
+        let account = &["account"];
+        let warehouse = &["account", "warehouse_id", "warehouse"];
+        let notifier = &["notifier"];
+
+        Self {
+            polls: register(
+                &registry,
+                IntCounterVec::new(opts("polls_total", "Supply polls"), account),
+            ),
+            poll_failures: register(
+                &registry,
+                IntCounterVec::new(opts("poll_failures_total", "Failed supply polls"), account),
+            ),
+            poll_duration: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new("poll_duration_seconds", "Supply poll duration")
+                        .namespace(NAMESPACE),
+                    account,
+                ),
+            ),
+            tracked_supplies: register(
+                &registry,
+                IntGaugeVec::new(opts("tracked_supplies", "Tracked supplies"), account),
+            ),
+            open_slots: register(
+                &registry,
+                IntGaugeVec::new(
+                    opts("open_slots", "Available acceptance dates per warehouse"),
+                    warehouse,
+                ),
+            ),
+            min_coefficient: register(
+                &registry,
+                GaugeVec::new(
+                    opts(
+                        "min_coefficient",
+                        "Minimum coefficient of available dates per warehouse",
+                    ),
+                    warehouse,
+                ),
+            ),
+            notifications: register(
+                &registry,
+                IntCounterVec::new(opts("notifications_total", "Notifications sent"), notifier),
+            ),
+            notification_failures: register(
+                &registry,
+                IntCounterVec::new(
+                    opts("notification_failures_total", "Failed notifications"),
+                    notifier,
+                ),
+            ),
+            credential_refreshes: register(
+                &registry,
+                IntCounterVec::new(
+                    opts(
+                        "credential_refreshes_total",
+                        "Credential refreshes from the browser",
+                    ),
+                    account,
+                ),
+            ),
+            credential_refresh_failures: register(
+                &registry,
+                IntCounterVec::new(
+                    opts(
+                        "credential_refresh_failures_total",
+                        "Failed credential refreshes",
+                    ),
+                    account,
+                ),
+            ),
+            browser_restarts: register(
+                &registry,
+                IntCounterVec::new(
+                    opts(
+                        "browser_restarts_total",
+                        "Browser restarts after a lost connection",
+                    ),
+                    account,
+                ),
+            ),
+            registry,
+        }
+    }
+
+    /// Все метрики в текстовом формате Prometheus
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::new();
+        // Запись в Vec не завершается ошибкой
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buf);
+        String::from_utf8(buf).unwrap_or_default()
+    }
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::io::Write;
+
+use super::{Event, Notifier, error::Result};
+use crate::{config::ConsoleFormat, i18n::msg};
+
+/// Вывод обновлений в консоль. Шаблоны не поддерживаются: вывод предназначен
+/// для отладки (`text`) или обработки программами (`json`), его формат не должен меняться
+pub struct ConsoleNotifier {
+    format: ConsoleFormat,
+}
+
+/// Строка JSON Lines: изменение одной даты приёмки
+#[derive(Serialize)]
+struct DateLine<'a> {
+    timestamp: &'a str,
+    account: &'a str,
+    /// `opened` — дата стала доступна, `closed` — снова недоступна
+    event: &'static str,
+    preorder_id: i64,
+    supply_id: Option<i64>,
+    warehouse_id: i64,
+    warehouse: &'a str,
+    date: &'a str,
+    coefficient: f64,
+    cost: f64,
+}
+
+impl ConsoleNotifier {
+    pub fn new(format: ConsoleFormat) -> Self {
+        Self { format }
+    }
+
+    fn print_text(event: &Event) {
+        if event.account.is_empty() {
+            println!("{}: {:#?}", msg().supplies_update, event.updates);
+        } else {
+            println!(
+                "{} ({}): {:#?}",
+                msg().supplies_update,
+                event.account,
+                event.updates
+            );
+        }
+    }
+
+    /// Вывод по строке на каждую дату в порядке preorder_id
+    fn print_json(event: &Event) -> std::io::Result<()> {
+        let timestamp = event.time.to_rfc3339();
+        let mut updates = event.updates.iter().collect::<Vec<_>>();
+        updates.sort_by_key(|(k, _)| **k);
+
+        let mut stdout = std::io::stdout().lock();
+        for (k, v) in updates {
+            for (kind, costs) in [("opened", &v.costs), ("closed", &v.closed_costs)] {
+                for c in costs {
+                    let line = DateLine {
+                        timestamp: &timestamp,
+                        account: &event.account,
+                        event: kind,
+                        preorder_id: *k,
+                        supply_id: v.supply.supply_id,
+                        warehouse_id: v.supply.warehouse_id,
+                        warehouse: &v.supply.warehouse_name,
+                        date: &c.date,
+                        coefficient: c.coefficient,
+                        cost: c.cost,
+                    };
+                    serde_json::to_writer(&mut stdout, &line)?;
+                    writeln!(stdout)?;
+                }
+            }
+        }
+        stdout.flush()
+    }
+}
+
+#[async_trait]
+impl Notifier for ConsoleNotifier {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        match self.format {
+            ConsoleFormat::Text => Self::print_text(event),
+            ConsoleFormat::Json => Self::print_json(event)?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use super::{
+    Event, Notifier,
+    error::NotifyError,
+    error::Result,
+    template::{self, Template},
+};
+
+/// Системные уведомления рабочего стола
+pub struct DesktopNotifier {
+    template: Template,
+}
+
+impl DesktopNotifier {
+    pub fn new(template: Template) -> Self {
+        Self { template }
+    }
+}
+
+#[async_trait]
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        for (k, v) in event.opened() {
+            let ctx =
+                template::supply_context(&event.account, *k, v, v.costs.iter().map(|c| (c, false)));
+            let body = self.template.render(&ctx);
+
+            notify_rust::Notification::new()
+                .appname("WbSupplies")
+                .summary(&event.label(&v.supply.warehouse_name))
+                .body(&body)
+                .show()
+                .map_err(|e| NotifyError::Custom(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+
+use super::{
+    Event, Notifier,
+    error::Result,
+    rate_limit::RateLimiter,
+    short_date,
+    template::{self, Template},
+};
+use crate::{i18n::msg, util};
+
+/// Максимальное количество embed в одном сообщении Discord
+const MAX_EMBEDS: usize = 10;
+
+/// Максимальное количество полей в одном embed
+const MAX_FIELDS: usize = 25;
+
+/// Уведомления через входящий webhook Discord (embeds)
+pub struct DiscordNotifier {
+    url: String,
+    template: Template,
+    limiter: RateLimiter,
+}
+
+impl DiscordNotifier {
+    pub fn new(url: impl Into<String>, template: Template) -> Self {
+        Self {
+            url: url.into(),
+            template,
+            limiter: RateLimiter::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        let mut opened = event.opened().collect::<Vec<_>>();
+        opened.sort_by_key(|(k, _)| **k);
+
+        let embeds = opened
+            .into_iter()
+            .map(|(k, v)| {
+                let supply = template::supply_context(&event.account, *k, v, std::iter::empty());
+                let len = v.costs.len().min(MAX_FIELDS);
+                let fields = v
+                    .costs
+                    .iter()
+                    .take(MAX_FIELDS)
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let cost = template::cost_context(c, false, i + 1 == len);
+                        json!({
+                            "name": short_date(&c.date),
+                            "value": self.template.render_scoped(&[&supply, &cost]),
+                            "inline": true
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                json!({
+                    "title": event.label(&v.supply.warehouse_name),
+                    "url": util::preorder_id_to_url(*k),
+                    "fields": fields
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        for chunk in embeds.chunks(MAX_EMBEDS) {
+            let body = json!({
+                "content": format!("🔊 {}", msg().supplies_update),
+                "embeds": chunk,
+            });
+            self.limiter.post_json(&self.url, &body).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        notify::rate_limit::tests::receiver,
+        tracking::SupplyUpdateAcceptanceCosts,
+        wbseller::models::{Cost, Supply},
+    };
+    use reqwest::StatusCode;
+    use std::collections::HashMap;
+
+    fn event(warehouse: &str) -> Event {
+        let update = SupplyUpdateAcceptanceCosts {
+            supply: Supply {
+                warehouse_name: warehouse.to_string(),
+                ..Default::default()
+            },
+            costs: vec![Cost {
+                date: "2026-10-20T00:00:00Z".to_string(),
+                coefficient: 1.0,
+                ..Default::default()
+            }],
+            closed_costs: Vec::new(),
+        };
+        Event::new(String::new(), HashMap::from([(123, update)]))
+    }
+
+    #[tokio::test]
+    async fn sends_embeds_after_rate_limit() {
+        let (url, received) = receiver(vec![(
+            StatusCode::TOO_MANY_REQUESTS,
+            Vec::new(),
+            r#"{"message": "You are being rate limited.", "retry_after": 0.01, "global": false}"#,
+        )])
+        .await;
+        let template = Template::parse("**x{{coefficient}}**").unwrap();
+
+        DiscordNotifier::new(url, template)
+            .notify(&event("Коледино"))
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(
+            received[1],
+            json!({
+                "content": format!("🔊 {}", msg().supplies_update),
+                "embeds": [{
+                    "title": "Коледино",
+                    "url": util::preorder_id_to_url(123),
+                    "fields": [{ "name": "2026-10-20", "value": "**x1**", "inline": true }]
+                }]
+            })
+        );
+    }
+}
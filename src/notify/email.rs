@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+};
+use std::time::Duration;
+
+use super::{
+    Event, Notifier,
+    error::NotifyError,
+    error::Result,
+    template::{self, Template},
+};
+use crate::{config, i18n::msg};
+
+/// Отправка сводки по появившимся датам на электронную почту
+pub struct EmailNotifier {
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    subject: String,
+    template: Template,
+    html_template: Template,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailNotifier {
+    pub fn new(cfg: &config::Email) -> Result<Self> {
+        let parse_mailbox = |s: &str| {
+            s.parse::<Mailbox>()
+                .map_err(|e| NotifyError::Custom(format!("{} {s}: {e}", msg().invalid_address)))
+        };
+
+        let builder = match cfg.security {
+            config::SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&cfg.host)
+            }
+            config::SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&cfg.host)
+                    .map_err(|e| NotifyError::Custom(e.to_string()))?
+            }
+            config::SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&cfg.host)
+                .map_err(|e| NotifyError::Custom(e.to_string()))?,
+        };
+
+        let mut builder = builder
+            .port(cfg.port)
+            .timeout(Some(Duration::from_millis(cfg.timeout_millis)));
+
+        if !cfg.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                cfg.username.clone(),
+                cfg.password.expose().to_string(),
+            ));
+        }
+
+        Ok(Self {
+            from: parse_mailbox(&cfg.from)?,
+            to: cfg
+                .to
+                .iter()
+                .map(|s| parse_mailbox(s))
+                .collect::<Result<_>>()?,
+            subject: if cfg.subject.is_empty() {
+                msg().email_subject.to_string()
+            } else {
+                cfg.subject.clone()
+            },
+            template: Template::load(&cfg.template, msg().email_template)
+                .map_err(NotifyError::Custom)?,
+            html_template: Template::load(&cfg.html_template, msg().email_html_template)
+                .map_err(NotifyError::Custom)?
+                .html(),
+            transport: builder.build(),
+        })
+    }
+
+    /// Отправка письма с текстовой и HTML-частью всем получателям
+    pub async fn send(&self, subject: &str, plain: String, html: String) -> Result<()> {
+        let mut builder = Message::builder().from(self.from.clone()).subject(subject);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+
+        let message = builder
+            .multipart(MultiPart::alternative_plain_html(plain, html))
+            .map_err(|e| NotifyError::Custom(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| NotifyError::Custom(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        if event.opened().next().is_none() {
+            return Ok(());
+        }
+
+        let ctx = template::event_context(event);
+        let plain = self.template.render(&ctx);
+        let html = self.html_template.render(&ctx);
+
+        self.send(&self.subject, plain, html).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tracking::SupplyUpdateAcceptanceCosts,
+        wbseller::models::{Cost, Supply},
+    };
+    use std::collections::HashMap;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    /// SMTP-сервер на свободном порту, принимающий одно письмо.
+    /// Возвращает порт и текст письма после команды DATA
+    async fn smtp_sink() -> (u16, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut tx = Some(tx);
+
+            write.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("DATA") {
+                    write.write_all(b"354 end with .\r\n").await.unwrap();
+                    let mut data = String::new();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        if line == "." {
+                            break;
+                        }
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    if let Some(tx) = tx.take() {
+                        let _ = tx.send(data);
+                    }
+                    b"250 queued\r\n"
+                } else if command.starts_with("QUIT") {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                write.write_all(reply).await.unwrap();
+            }
+        });
+
+        (port, rx)
+    }
+
+    fn template_file(name: &str, content: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("wbsupplies-{name}-{}.tmpl", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path.display().to_string()
+    }
+
+    fn event() -> Event {
+        let update = SupplyUpdateAcceptanceCosts {
+            supply: Supply {
+                warehouse_name: "Koledino <A&B>".to_string(),
+                ..Default::default()
+            },
+            costs: vec![Cost {
+                date: "2026-10-20T00:00:00Z".to_string(),
+                coefficient: 0.0,
+                ..Default::default()
+            }],
+            closed_costs: Vec::new(),
+        };
+        Event::new(String::new(), HashMap::from([(123, update)]))
+    }
+
+    #[tokio::test]
+    async fn sends_plain_and_html_parts() {
+        let (port, rx) = smtp_sink().await;
+        let cfg = config::Email {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: config::SmtpSecurity::None,
+            from: "bot@example.com".to_string(),
+            to: vec!["seller@example.com".to_string()],
+            subject: "Slots".to_string(),
+            template: template_file(
+                "plain",
+                "Plain{{#supplies}} {{warehouse}} {{preorder_id}}{{/supplies}}",
+            ),
+            html_template: template_file(
+                "html",
+                "<p>Html{{#supplies}} {{warehouse}} {{preorder_id}}{{/supplies}}</p>",
+            ),
+            ..Default::default()
+        };
+
+        let notifier = EmailNotifier::new(&cfg).unwrap();
+        let _ = std::fs::remove_file(&cfg.template);
+        let _ = std::fs::remove_file(&cfg.html_template);
+        notifier.notify(&event()).await.unwrap();
+
+        let data = rx.await.unwrap();
+        assert!(data.contains("Subject: Slots"));
+        assert!(data.contains("To: seller@example.com"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("Content-Type: text/plain; charset=utf-8"));
+        assert!(data.contains("Content-Type: text/html; charset=utf-8"));
+        // Текстовая часть без экранирования, HTML-часть с экранированием
+        assert!(data.contains("Plain Koledino <A&B> 123"));
+        assert!(data.contains("<p>Html Koledino &lt;A&amp;B&gt; 123</p>"));
+    }
+
+    #[tokio::test]
+    async fn skips_event_without_opened_dates() {
+        let (port, mut rx) = smtp_sink().await;
+        let cfg = config::Email {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: config::SmtpSecurity::None,
+            from: "bot@example.com".to_string(),
+            to: vec!["seller@example.com".to_string()],
+            ..Default::default()
+        };
+
+        let notifier = EmailNotifier::new(&cfg).unwrap();
+        notifier
+            .notify(&Event::new(String::new(), HashMap::new()))
+            .await
+            .unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+}
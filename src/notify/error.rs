@@ -0,0 +1,46 @@
+use reqwest::{Error as ReqwestError, StatusCode};
+use std::io::Error as StdIoError;
+use std::result::Result as StdResult;
+use thiserror::Error;
+
+pub type Result<T> = StdResult<T, NotifyError>;
+
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    #[error("TelegramError: {0}")]
+    Telegram(String),
+
+    /// Адрес запроса не сохраняется: в адресах webhook Slack и Discord содержится ключ доступа
+    #[error("ReqwestError: {0}")]
+    Reqwest(ReqwestError),
+
+    #[error("HTTP status: {0}")]
+    Status(StatusCode),
+
+    #[error("StdIoError: {0:?}")]
+    StdIo(#[from] StdIoError),
+
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl From<ReqwestError> for NotifyError {
+    fn from(e: ReqwestError) -> Self {
+        Self::Reqwest(e.without_url())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn hides_request_url() {
+        // Порт 1 закрыт: ошибка соединения
+        let url = "http://127.0.0.1:1/services/T000/B000/secret-key";
+        let error = NotifyError::from(reqwest::get(url).await.unwrap_err());
+
+        assert!(!error.to_string().contains("secret-key"));
+        assert!(!format!("{error:?}").contains("secret-key"));
+    }
+}
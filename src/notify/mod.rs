@@ -0,0 +1,178 @@
+mod console;
+mod desktop;
+mod discord;
+mod email;
+pub mod error;
+mod open;
+mod rate_limit;
+mod slack;
+mod telegram;
+pub mod template;
+mod webhook;
+
+pub use console::ConsoleNotifier;
+pub use desktop::DesktopNotifier;
+pub use discord::DiscordNotifier;
+pub use email::EmailNotifier;
+pub use open::OpenNotifier;
+pub use slack::SlackNotifier;
+pub use telegram::TelegramNotifier;
+pub use webhook::{EventPayload, WebhookNotifier};
+
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    config::{self, Config},
+    i18n::msg,
+    metrics,
+    tracking::{SupplyUpdateAcceptanceCosts, Trackers},
+};
+use error::Result;
+use template::Template;
+
+/// Событие обновления поставок
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub time: chrono::DateTime<chrono::Local>,
+    /// Название аккаунта. Пусто, если аккаунт один и не назван
+    pub account: String,
+    /// Изменения по поставкам: preorder_id -> обновление
+    pub updates: HashMap<i64, SupplyUpdateAcceptanceCosts>,
+}
+
+impl Event {
+    pub fn new(account: String, updates: HashMap<i64, SupplyUpdateAcceptanceCosts>) -> Self {
+        Self {
+            time: chrono::Local::now(),
+            account,
+            updates,
+        }
+    }
+
+    /// Название склада с аккаунтом: `аккаунт · склад`
+    pub fn label(&self, warehouse: &str) -> String {
+        account_label(&self.account, warehouse)
+    }
+
+    /// Поставки, по которым появились доступные даты
+    pub fn opened(&self) -> impl Iterator<Item = (&i64, &SupplyUpdateAcceptanceCosts)> {
+        self.updates.iter().filter(|(_, v)| !v.costs.is_empty())
+    }
+}
+
+/// Способ доставки уведомлений
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Название для вывода ошибок
+    fn name(&self) -> &'static str;
+
+    async fn notify(&self, event: &Event) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: Notifier> Notifier for Arc<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        (**self).notify(event).await
+    }
+}
+
+/// Создание получателей уведомлений по конфигурации.
+/// Телеграм-бот запускается один раз и сохраняется в `telegram`,
+/// при повторном вызове (перезагрузке конфигурации) обновляются его параметры
+pub async fn from_config(
+    cfg: &Config,
+    trackers: &Arc<Trackers>,
+    telegram: &mut Option<Arc<TelegramNotifier>>,
+) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+
+    for notifier in cfg.notifiers() {
+        match notifier {
+            config::Notifier::Telegram => {
+                if let Some(n) = telegram {
+                    n.update(cfg);
+                } else {
+                    *telegram = TelegramNotifier::start(cfg, trackers).await.map(Arc::new);
+                }
+                match telegram {
+                    Some(n) => notifiers.push(Box::new(Arc::clone(n))),
+                    None => tracing::warn!("{}", msg().telegram_not_configured),
+                }
+            }
+            config::Notifier::Console { format } => {
+                notifiers.push(Box::new(ConsoleNotifier::new(format)))
+            }
+            config::Notifier::Open { browser } => {
+                notifiers.push(Box::new(OpenNotifier::new(browser)))
+            }
+            config::Notifier::Webhook(webhook) => {
+                notifiers.push(Box::new(WebhookNotifier::new(webhook)))
+            }
+            config::Notifier::Desktop { template } => {
+                let template = Template::load_or_default(&template, msg().desktop_template);
+                notifiers.push(Box::new(DesktopNotifier::new(template)))
+            }
+            config::Notifier::Slack(webhook) => {
+                let template = Template::load_or_default(&webhook.template, msg().slack_template);
+                notifiers.push(Box::new(SlackNotifier::new(webhook.url.expose(), template)))
+            }
+            config::Notifier::Discord(webhook) => {
+                let template = Template::load_or_default(&webhook.template, msg().discord_template);
+                notifiers.push(Box::new(DiscordNotifier::new(
+                    webhook.url.expose(),
+                    template,
+                )))
+            }
+            config::Notifier::Email(email) => match EmailNotifier::new(&email) {
+                Ok(n) => notifiers.push(Box::new(n)),
+                Err(e) => tracing::warn!(error = %e, "{}", msg().invalid_email_params),
+            },
+        }
+    }
+
+    notifiers
+}
+
+/// Отправка события всем получателям
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], event: &Event) {
+    let results = futures::future::join_all(notifiers.iter().map(|n| n.notify(event))).await;
+
+    let metrics = metrics::get();
+    for (notifier, result) in notifiers.iter().zip(results) {
+        metrics
+            .notifications
+            .with_label_values(&[notifier.name()])
+            .inc();
+        if let Err(e) = result {
+            metrics
+                .notification_failures
+                .with_label_values(&[notifier.name()])
+                .inc();
+            tracing::warn!(
+                notifier = notifier.name(),
+                error = %e,
+                "{}",
+                msg().notify_failed
+            );
+        }
+    }
+}
+
+/// Название склада с аккаунтом, если аккаунт назван
+pub fn account_label(account: &str, warehouse: &str) -> String {
+    if account.is_empty() {
+        warehouse.to_string()
+    } else {
+        format!("{account} · {warehouse}")
+    }
+}
+
+/// Дата без времени: 2025-01-01T00:00:00Z -> 2025-01-01
+pub fn short_date(date: &str) -> &str {
+    date.split_once('T').map_or(date, |(d, _)| d)
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use super::{Event, Notifier, error::Result};
+use crate::util;
+
+/// Открытие поставок с появившимися датами в браузере
+pub struct OpenNotifier {
+    browser: String,
+}
+
+impl OpenNotifier {
+    pub fn new(browser: impl Into<String>) -> Self {
+        Self {
+            browser: browser.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for OpenNotifier {
+    fn name(&self) -> &'static str {
+        "open"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        for (k, _) in event.opened() {
+            open::with_in_background(util::preorder_id_to_url(*k), &self.browser);
+        }
+        Ok(())
+    }
+}
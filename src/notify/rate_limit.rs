@@ -0,0 +1,231 @@
+use reqwest::{Client, StatusCode, header::HeaderMap};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::error::{NotifyError, Result};
+
+/// Максимальное количество повторов после ответа 429
+const MAX_RETRIES: u32 = 3;
+
+/// Отправка запросов с соблюдением ограничений частоты платформы.
+/// Учитывает `Retry-After` и заголовки `X-RateLimit-*`
+pub struct RateLimiter {
+    client: Client,
+    /// Момент, до которого запросы отправлять нельзя
+    blocked_until: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            blocked_until: Mutex::new(None),
+        }
+    }
+
+    /// Отправка JSON POST-запроса
+    pub async fn post_json(&self, url: &str, body: &Value) -> Result<()> {
+        let mut retries = 0;
+
+        loop {
+            // Мьютекс не удерживается во время ожидания, чтобы не блокировать другие запросы
+            let blocked_until = *self.blocked_until.lock().await;
+            if let Some(until) = blocked_until {
+                tokio::time::sleep_until(until.into()).await;
+            }
+
+            let response = self
+                .client
+                .post(url)
+                .timeout(Duration::from_secs(10))
+                .json(body)
+                .send()
+                .await?;
+
+            let status = response.status();
+            let headers = response.headers().clone();
+
+            if status == StatusCode::TOO_MANY_REQUESTS && retries < MAX_RETRIES {
+                let body = response.json::<Value>().await.unwrap_or_default();
+                self.block_for(retry_after(&headers, &body)).await;
+                retries += 1;
+                continue;
+            }
+
+            // Исчерпан лимит запросов в текущем окне — ждём до его сброса
+            if header_f64(&headers, "x-ratelimit-remaining") == Some(0.)
+                && let Some(reset_after) = header_f64(&headers, "x-ratelimit-reset-after")
+            {
+                self.block_for(Duration::from_secs_f64(reset_after)).await;
+            }
+
+            if !status.is_success() {
+                return Err(NotifyError::Status(status));
+            }
+
+            return Ok(());
+        }
+    }
+
+    /// Запрет запросов на время `duration`. Более поздний запрет не сокращается
+    async fn block_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut blocked_until = self.blocked_until.lock().await;
+        *blocked_until = Some(blocked_until.map_or(until, |current| current.max(until)));
+    }
+}
+
+fn header_f64(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| v.is_finite() && *v >= 0.)
+}
+
+/// Время ожидания из заголовка `Retry-After` (в секундах)
+pub fn retry_after_header(headers: &HeaderMap) -> Option<Duration> {
+    header_f64(headers, "retry-after").map(retry_after_secs)
+}
+
+/// Время ожидания после ответа 429: заголовок `Retry-After` или поле `retry_after`
+fn retry_after(headers: &HeaderMap, body: &Value) -> Duration {
+    retry_after_header(headers)
+        .or_else(|| body["retry_after"].as_f64().map(retry_after_secs))
+        .unwrap_or(Duration::from_secs(1))
+}
+
+fn retry_after_secs(secs: f64) -> Duration {
+    Duration::from_secs_f64(secs.clamp(0., 300.))
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use axum::{Json, Router, routing::post};
+    use reqwest::header::HeaderValue;
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    /// Ответ получателя: код, заголовки и тело
+    pub type Reply = (StatusCode, Vec<(&'static str, &'static str)>, &'static str);
+
+    /// Принятые тела запросов
+    pub type Received = Arc<Mutex<Vec<Value>>>;
+
+    /// Получатель на свободном порту. Отвечает по очереди `replies`, затем — 204
+    pub async fn receiver(replies: Vec<Reply>) -> (String, Received) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = Received::default();
+        let replies = Arc::new(Mutex::new(VecDeque::from(replies)));
+
+        let app = Router::new().route(
+            "/hook",
+            post({
+                let received = Arc::clone(&received);
+                move |Json(body): Json<Value>| async move {
+                    received.lock().unwrap().push(body);
+                    let (status, headers, body) = replies.lock().unwrap().pop_front().unwrap_or((
+                        StatusCode::NO_CONTENT,
+                        Vec::new(),
+                        "",
+                    ));
+                    let mut map = HeaderMap::new();
+                    for (name, value) in headers {
+                        map.insert(name, HeaderValue::from_static(value));
+                    }
+                    (status, map, body)
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (url, received)
+    }
+
+    fn too_many_requests(retry_after: &'static str) -> Reply {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            vec![("retry-after", retry_after)],
+            "",
+        )
+    }
+
+    #[tokio::test]
+    async fn retries_after_retry_after_header_and_body() {
+        let (url, received) = receiver(vec![
+            too_many_requests("0"),
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                Vec::new(),
+                r#"{"retry_after": 0.01}"#,
+            ),
+        ])
+        .await;
+
+        RateLimiter::new()
+            .post_json(&url, &serde_json::json!({ "n": 1 }))
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        assert!(received.iter().all(|b| b["n"] == 1));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let replies = (0..=MAX_RETRIES).map(|_| too_many_requests("0")).collect();
+        let (url, received) = receiver(replies).await;
+
+        let error = RateLimiter::new()
+            .post_json(&url, &Value::Null)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            NotifyError::Status(StatusCode::TOO_MANY_REQUESTS)
+        ));
+        assert_eq!(received.lock().unwrap().len(), MAX_RETRIES as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn waits_for_window_reset() {
+        let (url, _) = receiver(vec![(
+            StatusCode::OK,
+            vec![
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset-after", "0.2"),
+            ],
+            "",
+        )])
+        .await;
+        let limiter = RateLimiter::new();
+
+        limiter.post_json(&url, &Value::Null).await.unwrap();
+        let started = Instant::now();
+        limiter.post_json(&url, &Value::Null).await.unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn does_not_hold_lock_while_waiting() {
+        let (url, _) = receiver(vec![too_many_requests("1")]).await;
+        let limiter = Arc::new(RateLimiter::new());
+
+        let sending = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.post_json(&url, &Value::Null).await }
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        assert!(limiter.blocked_until.try_lock().is_ok());
+        sending.await.unwrap().unwrap();
+    }
+}
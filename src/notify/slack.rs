@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+
+use super::{
+    Event, Notifier,
+    error::Result,
+    rate_limit::RateLimiter,
+    template::{self, Template},
+};
+use crate::{i18n::msg, util};
+
+/// Максимальное количество блоков в одном сообщении Slack
+const MAX_BLOCKS: usize = 50;
+
+/// Уведомления через входящий webhook Slack (Block Kit)
+pub struct SlackNotifier {
+    url: String,
+    template: Template,
+    limiter: RateLimiter,
+}
+
+impl SlackNotifier {
+    pub fn new(url: impl Into<String>, template: Template) -> Self {
+        Self {
+            url: url.into(),
+            // Символы `&`, `<` и `>` в значениях ломают разметку mrkdwn
+            template: template.mrkdwn(),
+            limiter: RateLimiter::new(),
+        }
+    }
+}
+
+/// Блоки по поставке: заголовок со складом, строка на каждую дату и ссылка
+fn supply_blocks(preorder_id: i64, warehouse: &str, rows: Vec<String>) -> Vec<Value> {
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": warehouse }
+    })];
+
+    blocks.extend(rows.into_iter().map(|row| {
+        json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": row }
+        })
+    }));
+
+    blocks.push(json!({
+        "type": "actions",
+        "elements": [{
+            "type": "button",
+            "text": { "type": "plain_text", "text": msg().open_supply },
+            "url": util::preorder_id_to_url(preorder_id)
+        }]
+    }));
+    blocks.push(json!({ "type": "divider" }));
+
+    blocks
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        let mut opened = event.opened().collect::<Vec<_>>();
+        opened.sort_by_key(|(k, _)| **k);
+
+        let mut messages: Vec<Vec<Value>> = Vec::new();
+        for (k, v) in opened {
+            let supply = template::supply_context(&event.account, *k, v, std::iter::empty());
+            let len = v.costs.len().min(MAX_BLOCKS - 3);
+            let rows = v
+                .costs
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let cost = template::cost_context(c, false, i + 1 == len);
+                    self.template.render_scoped(&[&supply, &cost])
+                })
+                .take(MAX_BLOCKS - 3)
+                .collect();
+            let blocks = supply_blocks(*k, &event.label(&v.supply.warehouse_name), rows);
+
+            match messages.last_mut() {
+                Some(last) if last.len() + blocks.len() <= MAX_BLOCKS => last.extend(blocks),
+                _ => messages.push(blocks),
+            }
+        }
+
+        for blocks in messages {
+            let body = json!({
+                "text": msg().supplies_update,
+                "blocks": blocks,
+            });
+            self.limiter.post_json(&self.url, &body).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        notify::rate_limit::tests::receiver,
+        tracking::SupplyUpdateAcceptanceCosts,
+        wbseller::models::{Cost, Supply},
+    };
+    use reqwest::StatusCode;
+    use std::collections::HashMap;
+
+    fn event(warehouse: &str) -> Event {
+        let update = SupplyUpdateAcceptanceCosts {
+            supply: Supply {
+                warehouse_name: warehouse.to_string(),
+                ..Default::default()
+            },
+            costs: vec![Cost {
+                date: "2026-10-20T00:00:00Z".to_string(),
+                coefficient: 1.0,
+                ..Default::default()
+            }],
+            closed_costs: Vec::new(),
+        };
+        Event::new(String::new(), HashMap::from([(123, update)]))
+    }
+
+    #[tokio::test]
+    async fn sends_escaped_blocks_after_rate_limit() {
+        let (url, received) = receiver(vec![(
+            StatusCode::TOO_MANY_REQUESTS,
+            vec![("retry-after", "0")],
+            "",
+        )])
+        .await;
+        let template = Template::parse("{{warehouse}}: *{{short_date}}* x{{coefficient}}").unwrap();
+
+        SlackNotifier::new(url, template)
+            .notify(&event("<A&B>"))
+            .await
+            .unwrap();
+
+        // Первый запрос отклонён с 429 и повторён
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0], received[1]);
+        assert_eq!(
+            received[1],
+            json!({
+                "text": msg().supplies_update,
+                "blocks": [
+                    { "type": "header", "text": { "type": "plain_text", "text": "<A&B>" } },
+                    {
+                        "type": "section",
+                        "text": { "type": "mrkdwn", "text": "&lt;A&amp;B&gt;: *2026-10-20* x1" }
+                    },
+                    {
+                        "type": "actions",
+                        "elements": [{
+                            "type": "button",
+                            "text": { "type": "plain_text", "text": msg().open_supply },
+                            "url": util::preorder_id_to_url(123)
+                        }]
+                    },
+                    { "type": "divider" }
+                ]
+            })
+        );
+    }
+}
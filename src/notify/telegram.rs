@@ -0,0 +1,391 @@
+use async_trait::async_trait;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::sync::{Mutex, mpsc};
+
+use super::{
+    Event, Notifier,
+    error::NotifyError,
+    error::Result,
+    short_date,
+    template::{self, Template},
+};
+use crate::{
+    config,
+    i18n::msg,
+    telebot::{
+        self, Bot, BotBuilder,
+        callback::CallbackAction,
+        types::{CallbackQuery, ChatTarget, InlineKeyboardMarkup, Update},
+    },
+    tracking::Trackers,
+    util,
+    wbseller::models::{Cost, Supply},
+};
+
+/// Даты поставки, о которых уведомляли: дата -> (Cost, доступна)
+type SupplyNotice = BTreeMap<String, (Cost, bool)>;
+
+/// Уведомления через телеграм-бота.
+/// По каждой поставке отправляется одно сообщение, которое затем редактируется
+pub struct TelegramNotifier {
+    bot: Arc<Bot>,
+    trackers: Arc<Trackers>,
+    options: RwLock<Arc<Options>>,
+    /// Уведомления по поставкам: preorder_id -> даты
+    notices: Arc<Mutex<HashMap<i64, SupplyNotice>>>,
+}
+
+/// Как часто удаляются сообщения по поставкам, которые больше не отслеживаются
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Параметры уведомлений, обновляемые при перезагрузке конфигурации
+struct Options {
+    screenshots: bool,
+    screenshot_selector: Option<String>,
+    template: Template,
+    /// Получатели уведомлений по аккаунтам, если заданы в `[[accounts]]`
+    account_targets: HashMap<String, Vec<ChatTarget>>,
+}
+
+impl Options {
+    fn new(cfg: &config::Config) -> Self {
+        let bot = &cfg.telegram_bot;
+        Self {
+            screenshots: bot.screenshots,
+            screenshot_selector: Some(bot.screenshot_selector.clone()).filter(|s| !s.is_empty()),
+            template: Template::load_or_default(&bot.template, msg().telegram_template).html(),
+            account_targets: cfg
+                .accounts()
+                .into_iter()
+                .filter(|a| !a.targets.is_empty())
+                .map(|a| (a.name, a.targets))
+                .collect(),
+        }
+    }
+}
+
+impl TelegramNotifier {
+    /// Создание бота и запуск обработки входящих обновлений.
+    /// Возвращает `None`, если не указан токен или получатели либо некорректны параметры webhook
+    pub async fn start(config: &config::Config, trackers: &Arc<Trackers>) -> Option<Self> {
+        let cfg = &config.telegram_bot;
+        let targets = cfg.notification_targets();
+        let options = Options::new(config);
+        if cfg.token.is_empty() || (targets.is_empty() && options.account_targets.is_empty()) {
+            return None;
+        }
+
+        let bot = Arc::new(
+            BotBuilder::new(cfg.token.expose())
+                .add_targets(targets)
+                .allow_users(cfg.allow_users.iter().copied())
+                .parse_mode("HTML")
+                .build(),
+        );
+
+        // Обработка нажатий на кнопки уведомлений
+        let (tx, updates_rx) = mpsc::channel(64);
+
+        match cfg.mode {
+            config::UpdatesMode::Polling => {
+                let bot = Arc::clone(&bot);
+                tokio::spawn(async move { bot.run_polling(tx).await });
+            }
+            config::UpdatesMode::Webhook => {
+                let webhook_config = match cfg.webhook.to_webhook_config() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        tracing::error!(error = %e, "{}", msg().webhook_server_error);
+                        return None;
+                    }
+                };
+                if let Err(e) = bot
+                    .set_webhook(&cfg.webhook.url, Some(&webhook_config.secret_token))
+                    .await
+                {
+                    tracing::error!(error = %e, "{}", msg().webhook_set_failed);
+                }
+
+                tokio::spawn(async move {
+                    if let Err(e) = telebot::webhook::serve(webhook_config, tx).await {
+                        tracing::error!(error = %e, "{}", msg().webhook_server_error);
+                    }
+                });
+            }
+        }
+
+        tokio::spawn(handle_updates(
+            Arc::clone(&bot),
+            Arc::clone(trackers),
+            updates_rx,
+        ));
+
+        // Без новых обновлений (например, запланирована последняя поставка)
+        // устаревшие сообщения удаляются по таймеру
+        let notices = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(cleanup_notices(
+            Arc::clone(&bot),
+            Arc::clone(trackers),
+            Arc::downgrade(&notices),
+        ));
+
+        Some(Self {
+            bot,
+            trackers: Arc::clone(trackers),
+            options: RwLock::new(Arc::new(options)),
+            notices,
+        })
+    }
+
+    /// Применение изменённой конфигурации: получатели, пользователи, шаблон и скриншоты.
+    /// Токен и способ получения обновлений меняются только после перезапуска
+    pub fn update(&self, config: &config::Config) {
+        let cfg = &config.telegram_bot;
+        self.bot.set_targets(cfg.notification_targets());
+        self.bot.set_allow_users(cfg.allow_users.clone());
+        *self.options.write().unwrap() = Arc::new(Options::new(config));
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        let options = Arc::clone(&self.options.read().unwrap());
+        let mut notices = self.notices.lock().await;
+        let mut errors = Vec::new();
+
+        let targets = match options.account_targets.get(&event.account) {
+            Some(targets) => targets.clone(),
+            None => self.bot.targets(),
+        };
+        let account = self.trackers.index_of(&event.account).unwrap_or_default();
+
+        for (k, v) in &event.updates {
+            let dates = notices.entry(*k).or_default();
+
+            for c in &v.costs {
+                dates.insert(c.date.clone(), (c.clone(), true));
+            }
+            // Закрывшиеся даты отмечаем только если о них уже уведомляли
+            for c in &v.closed_costs {
+                if let Some(entry) = dates.get_mut(&c.date) {
+                    *entry = (c.clone(), false);
+                }
+            }
+
+            if dates.is_empty() {
+                notices.remove(k);
+                continue;
+            }
+
+            // Снова закрывшиеся даты отмечаются как closed
+            let ctx = template::supply_context(
+                &event.account,
+                *k,
+                v,
+                dates.values().map(|(c, is_open)| (c, !*is_open)),
+            );
+            let message = options.template.render(&ctx);
+            let reply_markup = supply_notice_keyboard(*k, &v.supply, account);
+
+            // Скриншот календаря прикладывается только при появлении новых дат
+            let screenshot = match self.trackers.get(&event.account) {
+                Some(tracking_supplies)
+                    if options.screenshots
+                        && !v.costs.is_empty()
+                        && message.chars().count() <= telebot::CAPTION_MAX_LEN =>
+                {
+                    tracking_supplies
+                        .screenshot_supply(*k, options.screenshot_selector.as_deref())
+                        .await
+                        .ok()
+                }
+                _ => None,
+            };
+
+            let result = match screenshot {
+                Some(photo) => {
+                    self.bot
+                        .write_supply_photo(&targets, *k, photo, &message, Some(&reply_markup))
+                        .await
+                }
+                None => {
+                    self.bot
+                        .write_supply(&targets, *k, &message, Some(&reply_markup))
+                        .await
+                }
+            };
+            if let Err(e) = result {
+                errors.push(e);
+            }
+        }
+
+        remove_obsolete(&self.bot, &self.trackers, &mut notices).await;
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(NotifyError::Telegram(errors.join("; ")))
+        }
+    }
+}
+
+/// Поставки, которые больше не отслеживаются, удаляем вместе с сообщениями
+async fn remove_obsolete(bot: &Bot, trackers: &Trackers, notices: &mut HashMap<i64, SupplyNotice>) {
+    let mut tracked = HashSet::new();
+    for t in trackers.iter() {
+        tracked.extend(t.supplies.read_acceptance_costs().await.into_keys());
+    }
+    let obsolete: Vec<_> = notices
+        .keys()
+        .filter(|k| !tracked.contains(k))
+        .cloned()
+        .collect();
+
+    for k in obsolete {
+        notices.remove(&k);
+        let _ = bot.delete_supply(k).await;
+    }
+}
+
+/// Периодическое удаление устаревших сообщений, пока существует получатель уведомлений
+async fn cleanup_notices(
+    bot: Arc<Bot>,
+    trackers: Arc<Trackers>,
+    notices: std::sync::Weak<Mutex<HashMap<i64, SupplyNotice>>>,
+) {
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        let Some(notices) = notices.upgrade() else {
+            break;
+        };
+        remove_obsolete(&bot, &trackers, &mut *notices.lock().await).await;
+    }
+}
+
+/// Кнопки уведомления по поставке
+fn supply_notice_keyboard(
+    preorder_id: i64,
+    supply: &Supply,
+    account: usize,
+) -> Vec<Vec<InlineKeyboardMarkup>> {
+    vec![
+        vec![InlineKeyboardMarkup::url(
+            &supply.warehouse_name,
+            util::preorder_id_to_url(preorder_id),
+        )],
+        vec![
+            InlineKeyboardMarkup::callback(
+                msg().mute_warehouse_button,
+                CallbackAction::MuteWarehouse(supply.warehouse_id, account).encode(),
+            ),
+            InlineKeyboardMarkup::callback(
+                msg().mute_supply_button,
+                CallbackAction::MuteSupply(preorder_id).encode(),
+            ),
+        ],
+        vec![InlineKeyboardMarkup::callback(
+            msg().all_dates_button,
+            CallbackAction::ShowDates(preorder_id).encode(),
+        )],
+    ]
+}
+
+/// Обработка входящих обновлений телеграм-бота
+async fn handle_updates(bot: Arc<Bot>, trackers: Arc<Trackers>, mut rx: mpsc::Receiver<Update>) {
+    while let Some(update) = rx.recv().await {
+        if let Some(query) = update.callback_query {
+            handle_callback_query(&bot, &trackers, query).await;
+        }
+    }
+}
+
+/// Выполнение действия кнопки и подтверждение нажатия
+async fn handle_callback_query(bot: &Bot, trackers: &Trackers, query: CallbackQuery) {
+    if !bot.is_allowed(query.from.id) {
+        let _ = bot
+            .answer_callback_query(&query.id, Some(msg().access_denied))
+            .await;
+        return;
+    }
+
+    let Some(action) = query.data.as_deref().and_then(|d| d.parse().ok()) else {
+        let _ = bot.answer_callback_query(&query.id, None).await;
+        return;
+    };
+
+    let answer = match action {
+        CallbackAction::MuteWarehouse(id, account) => {
+            if let Some(tracking_supplies) = trackers.get_index(account) {
+                tracking_supplies
+                    .mute_warehouse(id, Duration::from_secs(24 * 60 * 60))
+                    .await;
+            }
+            Some(msg().warehouse_muted)
+        }
+        CallbackAction::MuteSupply(id) => {
+            // preorder_id уникален, поэтому поставку можно отключить во всех аккаунтах
+            for t in trackers.iter() {
+                t.supplies.mute_supply(id).await;
+            }
+            Some(msg().supply_muted)
+        }
+        CallbackAction::ShowDates(id) => {
+            let mut costs = match trackers.find_supply(id).await {
+                Some(tracking_supplies) => tracking_supplies
+                    .read_acceptance_costs()
+                    .await
+                    .remove(&id)
+                    .map(|m| m.into_values().filter(|c| c.coefficient >= 0.).collect())
+                    .unwrap_or_else(Vec::new),
+                None => Vec::new(),
+            };
+            costs.sort_by(|a, b| a.date.cmp(&b.date));
+
+            let message = available_dates_message(&costs);
+            if let Some(message_from) = query.message
+                && let Some(chat) = message_from.chat
+            {
+                let target = ChatTarget {
+                    chat: chat.id.into(),
+                    thread_id: message_from.message_thread_id,
+                };
+                let _ = bot.send_message(&target, &message, None).await;
+            }
+            None
+        }
+    };
+
+    let _ = bot.answer_callback_query(&query.id, answer).await;
+}
+
+/// Формирование списка доступных дат поставки
+fn available_dates_message(costs: &[Cost]) -> String {
+    if costs.is_empty() {
+        return msg().no_available_dates.to_string();
+    }
+
+    let mut message = format!("{}\n\n", msg().available_dates);
+    for c in costs {
+        message.push_str(&format!(
+            "<b>{}</b> — {} <b>{}</b>, {} <b>{}</b>\n",
+            short_date(&c.date),
+            msg().coefficient,
+            c.coefficient,
+            msg().cost,
+            c.cost
+        ));
+    }
+    message
+}
@@ -0,0 +1,548 @@
+//! Шаблоны уведомлений.
+//!
+//! Синтаксис:
+//! - `{{name}}` — подстановка значения;
+//! - `{{#name}}...{{/name}}` — повтор для каждого элемента списка или вывод, если значение истинно;
+//! - `{{^name}}...{{/name}}` — вывод, если значение ложно или список пуст.
+
+use std::collections::HashMap;
+
+use super::{Event, short_date};
+use crate::{i18n::msg, tracking::SupplyUpdateAcceptanceCosts, util, wbseller::models::Cost};
+
+/// Значение, доступное в шаблоне
+#[derive(Debug, Clone)]
+pub enum Value {
+    Str(String),
+    Bool(bool),
+    List(Vec<Context>),
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<Vec<Context>> for Value {
+    fn from(value: Vec<Context>) -> Self {
+        Self::List(value)
+    }
+}
+
+/// Набор значений для подстановки
+#[derive(Debug, Clone, Default)]
+pub struct Context(HashMap<&'static str, Value>);
+
+impl Context {
+    pub fn with(mut self, key: &'static str, value: impl Into<Value>) -> Self {
+        self.0.insert(key, value.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Var(String),
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+/// Экранирование подставляемых значений
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Escape {
+    None,
+    Html,
+    /// Содержимое строки JSON: значение подставляется между кавычками шаблона
+    Json,
+    /// Разметка Slack mrkdwn
+    Mrkdwn,
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+    escape: Escape,
+}
+
+impl Template {
+    pub fn parse(src: &str) -> Result<Self, String> {
+        // Стек открытых секций: (имя, инвертирована, узлы)
+        let mut stack: Vec<(String, bool, Vec<Node>)> = vec![(String::new(), false, Vec::new())];
+        let mut rest = src;
+
+        while let Some(start) = rest.find("{{") {
+            let nodes = &mut stack.last_mut().unwrap().2;
+            if start > 0 {
+                nodes.push(Node::Text(rest[..start].to_string()));
+            }
+
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| msg().unclosed_tag.to_string())?;
+            let tag = after[..end].trim();
+            rest = &after[end + 2..];
+
+            if let Some(name) = tag.strip_prefix('#') {
+                stack.push((name.trim().to_string(), false, Vec::new()));
+            } else if let Some(name) = tag.strip_prefix('^') {
+                stack.push((name.trim().to_string(), true, Vec::new()));
+            } else if let Some(name) = tag.strip_prefix('/') {
+                let name = name.trim();
+                if stack.len() < 2 {
+                    return Err(format!("{} {{{{/{name}}}}}", msg().unexpected_closing_tag));
+                }
+                let (open, inverted, children) = stack.pop().unwrap();
+                if open != name {
+                    return Err(format!(
+                        "{}: {{{{#{open}}}}} … {{{{/{name}}}}}",
+                        msg().mismatched_tags
+                    ));
+                }
+                stack.last_mut().unwrap().2.push(Node::Section {
+                    name: open,
+                    inverted,
+                    children,
+                });
+            } else {
+                nodes.push(Node::Var(tag.to_string()));
+            }
+        }
+
+        if stack.len() > 1 {
+            let (open, ..) = stack.pop().unwrap();
+            return Err(format!("{} {{{{#{open}}}}}", msg().unclosed_section));
+        }
+
+        let (_, _, mut nodes) = stack.pop().unwrap();
+        if !rest.is_empty() {
+            nodes.push(Node::Text(rest.to_string()));
+        }
+
+        Ok(Self {
+            nodes,
+            escape: Escape::None,
+        })
+    }
+
+    /// Загрузка шаблона из файла. Если путь пуст — используется шаблон по умолчанию
+    pub fn load(path: &str, default: &str) -> Result<Self, String> {
+        if path.is_empty() {
+            return Self::parse(default);
+        }
+        let src = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        Self::parse(&src).map_err(|e| format!("{path}: {e}"))
+    }
+
+    /// Загрузка шаблона с выводом ошибки и возвратом к шаблону по умолчанию
+    pub fn load_or_default(path: &str, default: &str) -> Self {
+        Self::load(path, default).unwrap_or_else(|e| {
+            tracing::warn!(path, error = %e, "{}", msg().template_error);
+            Self::parse(default)
+                .unwrap_or_else(|e| panic!("{}: {e}", msg().invalid_default_template))
+        })
+    }
+
+    /// Включение экранирования HTML
+    pub fn html(mut self) -> Self {
+        self.escape = Escape::Html;
+        self
+    }
+
+    /// Включение экранирования для строк JSON
+    pub fn json(mut self) -> Self {
+        self.escape = Escape::Json;
+        self
+    }
+
+    /// Включение экранирования для Slack mrkdwn
+    pub fn mrkdwn(mut self) -> Self {
+        self.escape = Escape::Mrkdwn;
+        self
+    }
+
+    pub fn render(&self, ctx: &Context) -> String {
+        self.render_scoped(&[ctx])
+    }
+
+    /// Подстановка с вложенными контекстами: значения ищутся с последнего
+    pub fn render_scoped(&self, scopes: &[&Context]) -> String {
+        let mut out = String::new();
+        self.render_nodes(&self.nodes, &mut scopes.to_vec(), &mut out);
+        out
+    }
+
+    fn render_nodes(&self, nodes: &[Node], stack: &mut Vec<&Context>, out: &mut String) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Var(name) => match lookup(stack, name) {
+                    Some(Value::Str(s)) => match self.escape {
+                        Escape::None => out.push_str(s),
+                        Escape::Html => out.push_str(&escape_html(s)),
+                        Escape::Json => out.push_str(&escape_json(s)),
+                        Escape::Mrkdwn => out.push_str(&escape_mrkdwn(s)),
+                    },
+                    Some(Value::Bool(b)) => out.push_str(&b.to_string()),
+                    _ => {}
+                },
+                Node::Section {
+                    name,
+                    inverted,
+                    children,
+                } => match (lookup(stack, name), inverted) {
+                    (Some(Value::List(items)), false) => {
+                        for item in items {
+                            stack.push(item);
+                            self.render_nodes(children, stack, out);
+                            stack.pop();
+                        }
+                    }
+                    (value, inverted) => {
+                        let truthy = match value {
+                            Some(Value::Str(s)) => !s.is_empty(),
+                            Some(Value::Bool(b)) => *b,
+                            Some(Value::List(items)) => !items.is_empty(),
+                            None => false,
+                        };
+                        if truthy != *inverted {
+                            self.render_nodes(children, stack, out);
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+fn lookup<'a>(stack: &[&'a Context], name: &str) -> Option<&'a Value> {
+    stack.iter().rev().find_map(|ctx| ctx.0.get(name))
+}
+
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Экранирование управляющих символов Slack mrkdwn
+pub fn escape_mrkdwn(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Экранирование содержимого строки JSON (без кавычек)
+pub fn escape_json(s: &str) -> String {
+    let quoted = serde_json::Value::from(s).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Контекст даты: `date`, `short_date`, `coefficient`, `cost`, `closed`, `last`
+pub fn cost_context(cost: &Cost, closed: bool, last: bool) -> Context {
+    Context::default()
+        .with("date", cost.date.as_str())
+        .with("short_date", short_date(&cost.date))
+        .with("coefficient", cost.coefficient.to_string())
+        .with("cost", cost.cost.to_string())
+        .with("closed", closed)
+        .with("last", last)
+}
+
+/// Контекст поставки: `account`, `preorder_id`, `supply_id`, `warehouse`, `warehouse_id`, `url`
+/// и список дат `costs`
+pub fn supply_context<'a>(
+    account: &str,
+    preorder_id: i64,
+    update: &SupplyUpdateAcceptanceCosts,
+    costs: impl ExactSizeIterator<Item = (&'a Cost, bool)>,
+) -> Context {
+    let len = costs.len();
+    let costs = costs
+        .enumerate()
+        .map(|(i, (c, closed))| cost_context(c, closed, i + 1 == len))
+        .collect::<Vec<_>>();
+
+    Context::default()
+        .with("account", account)
+        .with("preorder_id", preorder_id.to_string())
+        .with(
+            "supply_id",
+            update
+                .supply
+                .supply_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        )
+        .with("warehouse", update.supply.warehouse_name.as_str())
+        .with("warehouse_id", update.supply.warehouse_id.to_string())
+        .with("url", util::preorder_id_to_url(preorder_id))
+        .with("costs", costs)
+}
+
+/// Контекст события: `account`, `time` и список поставок с появившимися датами `supplies`
+pub fn event_context(event: &Event) -> Context {
+    let mut opened = event.opened().collect::<Vec<_>>();
+    opened.sort_by_key(|(k, _)| **k);
+
+    let supplies = opened
+        .into_iter()
+        .map(|(k, v)| supply_context(&event.account, *k, v, v.costs.iter().map(|c| (c, false))))
+        .collect::<Vec<_>>();
+
+    Context::default()
+        .with("account", event.account.as_str())
+        .with("time", event.time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .with("supplies", supplies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        i18n::{Language, Messages},
+        wbseller::models::Supply,
+    };
+
+    const URL: &str = "https://seller.wildberries.ru/supplies-management/all-supplies/supply-detail?preorderId=123&supplyId";
+
+    fn messages() -> &'static Messages {
+        Language::Ru.messages()
+    }
+
+    fn cost(date: &str, coefficient: f64, cost: f64) -> Cost {
+        Cost {
+            date: date.to_string(),
+            coefficient,
+            cost,
+            ..Default::default()
+        }
+    }
+
+    fn update(warehouse: &str) -> SupplyUpdateAcceptanceCosts {
+        SupplyUpdateAcceptanceCosts {
+            supply: Supply {
+                warehouse_name: warehouse.to_string(),
+                ..Default::default()
+            },
+            costs: vec![
+                cost("2026-10-20T00:00:00Z", 1.0, 2.5),
+                cost("2026-10-21T00:00:00Z", 0.0, 0.0),
+            ],
+            closed_costs: Vec::new(),
+        }
+    }
+
+    fn render(src: &str, ctx: &Context) -> String {
+        Template::parse(src).unwrap().render(ctx)
+    }
+
+    #[test]
+    fn telegram_default_matches_previous_message() {
+        let update = update("Коледино");
+        // Вторая дата снова закрылась
+        let ctx = supply_context("", 123, &update, update.costs.iter().zip([false, true]));
+        let message = Template::parse(messages().telegram_template)
+            .unwrap()
+            .html()
+            .render(&ctx);
+
+        assert_eq!(
+            message,
+            "🔊 <b><i>Обновление поставок</i></b>\n\n\
+             ▫️ <b>Коледино</b>\n\
+             Коэффициент: <b>1</b>\nСтоимость: <b>2.5</b>\nДата: <b>2026-10-20</b>\n\
+             \n\
+             <s>Коэффициент: <b>0</b>\nСтоимость: <b>0</b>\nДата: <b>2026-10-21</b>\n</s>"
+        );
+    }
+
+    #[test]
+    fn telegram_default_shows_account() {
+        let update = update("Коледино");
+        let ctx = supply_context("main", 123, &update, std::iter::empty());
+        let message = render(messages().telegram_template, &ctx);
+
+        assert!(message.starts_with("🔊 <b><i>Обновление поставок</i></b>\n👤 <b>main</b>\n\n▫️"));
+    }
+
+    #[test]
+    fn email_defaults_match_previous_message() {
+        let event = Event::new(
+            String::new(),
+            HashMap::from([(123, update("Коледино <СЦ>"))]),
+        );
+        let ctx = event_context(&event);
+
+        assert_eq!(
+            render(messages().email_template, &ctx),
+            format!(
+                "Обновление поставок\n\n\
+                 ▫️ Коледино <СЦ>\n\
+                 Коэффициент: 1\nСтоимость: 2.5\nДата: 2026-10-20\n\n\
+                 Коэффициент: 0\nСтоимость: 0\nДата: 2026-10-21\n\n\
+                 {URL}\n\n"
+            )
+        );
+
+        let html = Template::parse(messages().email_html_template)
+            .unwrap()
+            .html()
+            .render(&ctx);
+        assert_eq!(
+            html,
+            format!(
+                "<h3>Обновление поставок</h3>\
+                 <p><b><a href=\"{}\">Коледино &lt;СЦ&gt;</a></b></p>\
+                 <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+                 <tr><th>Дата</th><th>Коэффициент</th><th>Стоимость</th></tr>\
+                 <tr><td>2026-10-20</td><td>1</td><td>2.5</td></tr>\
+                 <tr><td>2026-10-21</td><td>0</td><td>0</td></tr>\
+                 </table>",
+                URL.replace('&', "&amp;")
+            )
+        );
+    }
+
+    #[test]
+    fn desktop_default_matches_previous_message() {
+        let update = update("Коледино");
+        let ctx = supply_context("", 123, &update, update.costs.iter().map(|c| (c, false)));
+
+        assert_eq!(
+            render(messages().desktop_template, &ctx),
+            "2026-10-20 — x1\n2026-10-21 — x0"
+        );
+    }
+
+    #[test]
+    fn slack_and_discord_defaults_match_previous_message() {
+        let update = update("Коледино");
+        let supply = supply_context("", 123, &update, std::iter::empty());
+        let cost = cost_context(&update.costs[0], false, false);
+
+        let slack = Template::parse(messages().slack_template).unwrap();
+        assert_eq!(
+            slack.render_scoped(&[&supply, &cost]),
+            "*2026-10-20* — коэффициент *1*, стоимость *2.5*"
+        );
+
+        let discord = Template::parse(messages().discord_template).unwrap();
+        assert_eq!(
+            discord.render_scoped(&[&supply, &cost]),
+            "Коэффициент: **1**\nСтоимость: **2.5**"
+        );
+    }
+
+    #[test]
+    fn all_default_templates_parse() {
+        for lang in [Language::Ru, Language::En] {
+            let m = lang.messages();
+            for src in [
+                m.telegram_template,
+                m.email_template,
+                m.email_html_template,
+                m.desktop_template,
+                m.slack_template,
+                m.discord_template,
+            ] {
+                assert!(Template::parse(src).is_ok(), "{src}");
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(Template::parse("{{#a}}x").is_err());
+        assert!(Template::parse("{{#a}}{{#b}}x{{/b}}").is_err());
+        assert!(Template::parse("x{{/a}}").is_err());
+        assert!(Template::parse("{{#a}}x{{/b}}").is_err());
+        assert!(Template::parse("{{name").is_err());
+    }
+
+    #[test]
+    fn escapes_values_only_in_html() {
+        let ctx = Context::default().with("v", "<a href=\"x\">&</a>");
+
+        assert_eq!(render("<b>{{v}}</b>", &ctx), "<b><a href=\"x\">&</a></b>");
+        assert_eq!(
+            Template::parse("<b>{{v}}</b>").unwrap().html().render(&ctx),
+            "<b>&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;</b>"
+        );
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        let ctx = Context::default().with("v", "Склад \"A\"\n\\");
+        let body = Template::parse(r#"{"v": "{{v}}"}"#)
+            .unwrap()
+            .json()
+            .render(&ctx);
+
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["v"], "Склад \"A\"\n\\");
+    }
+
+    #[test]
+    fn renders_sections() {
+        let items = vec![
+            Context::default().with("n", "1"),
+            Context::default().with("n", "2"),
+        ];
+        let ctx = Context::default()
+            .with("items", items)
+            .with("empty", Vec::<Context>::new())
+            .with("yes", true)
+            .with("no", false)
+            .with("text", "")
+            .with("outer", "o");
+
+        assert_eq!(
+            render("{{#items}}{{n}}{{outer}},{{/items}}", &ctx),
+            "1o,2o,"
+        );
+        assert_eq!(render("{{#yes}}y{{/yes}}{{#no}}n{{/no}}", &ctx), "y");
+        assert_eq!(
+            render("{{#text}}t{{/text}}{{#missing}}m{{/missing}}", &ctx),
+            ""
+        );
+    }
+
+    #[test]
+    fn renders_inverted_sections() {
+        let ctx = Context::default()
+            .with("items", vec![Context::default()])
+            .with("empty", Vec::<Context>::new())
+            .with("yes", true)
+            .with("no", false)
+            .with("text", "");
+
+        assert_eq!(render("{{^items}}i{{/items}}", &ctx), "");
+        assert_eq!(render("{{^yes}}y{{/yes}}", &ctx), "");
+        assert_eq!(
+            render(
+                "{{^empty}}e{{/empty}}{{^no}}n{{/no}}{{^text}}t{{/text}}{{^missing}}m{{/missing}}",
+                &ctx
+            ),
+            "entm"
+        );
+    }
+}
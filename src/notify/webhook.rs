@@ -0,0 +1,502 @@
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reqwest::{
+    Client, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+use serde::Serialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::{path::PathBuf, time::Duration};
+use tokio::io::AsyncWriteExt;
+
+use super::{
+    Event, Notifier,
+    error::NotifyError,
+    error::Result,
+    rate_limit,
+    template::{self, Template},
+};
+use crate::{config, i18n::msg, util, wbseller::models::Cost};
+
+/// Заголовок с подписью тела запроса
+const SIGNATURE_HEADER: &str = "x-wbsupplies-signature";
+
+/// Версия схемы события
+const SCHEMA_VERSION: u32 = 1;
+
+/// Событие в стабильном формате
+#[derive(Debug, Serialize)]
+pub struct EventPayload<'a> {
+    pub version: u32,
+    pub time: String,
+    /// Название аккаунта. Не передаётся, если аккаунт один и не назван
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub account: &'a str,
+    pub supplies: Vec<SupplyPayload<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SupplyPayload<'a> {
+    pub preorder_id: i64,
+    pub supply_id: Option<i64>,
+    pub warehouse_id: i64,
+    pub warehouse: &'a str,
+    pub url: String,
+    /// Даты, ставшие доступными
+    pub opened: Vec<CostPayload<'a>>,
+    /// Даты, снова ставшие недоступными
+    pub closed: Vec<CostPayload<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CostPayload<'a> {
+    pub date: &'a str,
+    pub coefficient: f64,
+    pub cost: f64,
+}
+
+impl<'a> From<&'a Cost> for CostPayload<'a> {
+    fn from(c: &'a Cost) -> Self {
+        Self {
+            date: &c.date,
+            coefficient: c.coefficient,
+            cost: c.cost,
+        }
+    }
+}
+
+impl<'a> From<&'a Event> for EventPayload<'a> {
+    fn from(event: &'a Event) -> Self {
+        let mut supplies = event
+            .updates
+            .iter()
+            .map(|(k, v)| SupplyPayload {
+                preorder_id: *k,
+                supply_id: v.supply.supply_id,
+                warehouse_id: v.supply.warehouse_id,
+                warehouse: &v.supply.warehouse_name,
+                url: util::preorder_id_to_url(*k),
+                opened: v.costs.iter().map(CostPayload::from).collect(),
+                closed: v.closed_costs.iter().map(CostPayload::from).collect(),
+            })
+            .collect::<Vec<_>>();
+        supplies.sort_by_key(|s| s.preorder_id);
+
+        Self {
+            version: SCHEMA_VERSION,
+            time: event.time.to_rfc3339(),
+            account: &event.account,
+            supplies,
+        }
+    }
+}
+
+/// Отправка обновлений HTTP POST-запросом. Тело запроса — JSON со стабильной схемой
+/// или по шаблону `body_template`, значения в котором экранируются для строк JSON
+pub struct WebhookNotifier {
+    cfg: config::OutgoingWebhook,
+    headers: HeaderMap,
+    template: Option<Template>,
+    /// Файл недоставленных событий
+    dead_letter_file: Option<PathBuf>,
+    client: Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(cfg: config::OutgoingWebhook) -> Self {
+        // Некорректные заголовки отклоняются при проверке конфигурации
+        let headers = cfg
+            .headers
+            .iter()
+            .filter_map(|(k, v)| {
+                let name = HeaderName::from_bytes(k.as_bytes()).ok()?;
+                let value = HeaderValue::from_str(v).ok()?;
+                Some((name, value))
+            })
+            .collect();
+
+        // Некорректный шаблон отклоняется при проверке конфигурации,
+        // при ошибке чтения тело формируется по `format`
+        let template = (!cfg.body_template.is_empty())
+            .then(|| Template::load(&cfg.body_template, ""))
+            .and_then(|template| {
+                template
+                    .inspect_err(|e| {
+                        tracing::warn!(path = cfg.body_template, error = %e, "{}", msg().template_error)
+                    })
+                    .ok()
+            })
+            .map(Template::json);
+        let dead_letter_file =
+            (!cfg.dead_letter_file.is_empty()).then(|| config::data_path(&cfg.dead_letter_file));
+
+        Self {
+            cfg,
+            headers,
+            template,
+            dead_letter_file,
+            client: Client::new(),
+        }
+    }
+
+    /// Формирование тела запроса
+    fn body(&self, event: &Event) -> Result<Vec<u8>> {
+        if let Some(template) = &self.template {
+            return Ok(template
+                .render(&template::event_context(event))
+                .into_bytes());
+        }
+
+        let body = match self.cfg.format {
+            config::WebhookFormat::Event => serde_json::to_vec(&EventPayload::from(event)),
+            config::WebhookFormat::Raw => serde_json::to_vec(&json!({
+                "time": event.time.to_rfc3339(),
+                "updates": event.updates,
+            })),
+        };
+
+        body.map_err(|e| NotifyError::Custom(e.to_string()))
+    }
+
+    /// Подпись тела запроса HMAC-SHA256
+    fn signature(&self, body: &[u8]) -> Option<String> {
+        if self.cfg.secret.is_empty() {
+            return None;
+        }
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.cfg.secret.expose().as_bytes()).ok()?;
+        mac.update(body);
+
+        Some(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    /// Отправка запроса. При ошибке возвращается также время ожидания из `Retry-After`
+    async fn send(&self, body: &[u8]) -> std::result::Result<(), (NotifyError, Option<Duration>)> {
+        let mut request = self
+            .client
+            .post(&self.cfg.url)
+            .timeout(Duration::from_millis(self.cfg.timeout_millis))
+            .headers(self.headers.clone())
+            .header("content-type", "application/json")
+            .body(body.to_vec());
+
+        if let Some(signature) = self.signature(body) {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let response = request.send().await.map_err(|e| (e.into(), None))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let retry_after = (status == StatusCode::TOO_MANY_REQUESTS)
+            .then(|| rate_limit::retry_after_header(response.headers()))
+            .flatten();
+        Err((NotifyError::Status(status), retry_after))
+    }
+
+    /// Запись неотправленного события в файл
+    async fn dead_letter(&self, body: &[u8], error: &NotifyError) -> std::io::Result<()> {
+        let Some(path) = &self.dead_letter_file else {
+            return Ok(());
+        };
+
+        let line = json!({
+            "time": chrono::Local::now().to_rfc3339(),
+            "url": self.cfg.url,
+            "error": error.to_string(),
+            "body": String::from_utf8_lossy(body),
+        });
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        file.write_all(format!("{line}\n").as_bytes()).await?;
+        // tokio::fs::File дописывает данные в фоне, без flush они могут потеряться
+        file.flush().await
+    }
+}
+
+/// Стоит ли повторять запрос после ошибки
+fn is_retryable(error: &NotifyError) -> bool {
+    match error {
+        NotifyError::Status(status) => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        NotifyError::Reqwest(_) => true,
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn notify(&self, event: &Event) -> Result<()> {
+        let body = self.body(event)?;
+        let max_backoff = Duration::from_millis(self.cfg.max_backoff_millis);
+        let mut backoff = Duration::from_millis(self.cfg.backoff_millis).min(max_backoff);
+        let mut attempt = 0;
+
+        loop {
+            let (error, retry_after) = match self.send(&body).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+
+            if attempt >= self.cfg.retries || !is_retryable(&error) {
+                // Ошибка записи не заменяет ошибку доставки
+                if let Err(e) = self.dead_letter(&body, &error).await {
+                    tracing::warn!(
+                        file = self.cfg.dead_letter_file,
+                        error = %e,
+                        "{}",
+                        msg().dead_letter_failed
+                    );
+                }
+                return Err(error);
+            }
+
+            // Задержка из `Retry-After` или экспоненциальная, не больше `max_backoff_millis`
+            tokio::time::sleep(retry_after.unwrap_or(backoff)).await;
+            backoff = (backoff * 2).min(max_backoff);
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tracking::SupplyUpdateAcceptanceCosts, wbseller::models::Supply};
+    use axum::{Router, body::Bytes, extract::State, http::HeaderMap, routing::post};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    const SECRET: &str = "test-secret";
+
+    /// Принятый запрос: подпись и тело
+    type Requests = Arc<Mutex<Vec<(Option<String>, Bytes)>>>;
+
+    /// Получатель на свободном порту, отвечающий кодом `status`
+    async fn start(status: StatusCode) -> (String, Requests) {
+        start_with(status, None).await
+    }
+
+    /// Получатель, отвечающий кодом `status` и заголовком `Retry-After`
+    async fn start_with(
+        status: StatusCode,
+        retry_after: Option<&'static str>,
+    ) -> (String, Requests) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let requests = Requests::default();
+
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    move |State(requests): State<Requests>, headers: HeaderMap, body: Bytes| async move {
+                        let signature = headers
+                            .get(SIGNATURE_HEADER)
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string);
+                        requests.lock().unwrap().push((signature, body));
+                        let mut headers = HeaderMap::new();
+                        if let Some(retry_after) = retry_after {
+                            headers.insert("retry-after", HeaderValue::from_static(retry_after));
+                        }
+                        (status, headers)
+                    },
+                ),
+            )
+            .with_state(Arc::clone(&requests));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (url, requests)
+    }
+
+    fn notifier(url: String, dead_letter_file: &std::path::Path) -> WebhookNotifier {
+        WebhookNotifier::new(config::OutgoingWebhook {
+            url,
+            secret: SECRET.to_string().into(),
+            retries: 2,
+            backoff_millis: 1,
+            dead_letter_file: dead_letter_file.display().to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn dead_letter_path(name: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("wbsupplies-{name}-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn expected_signature(body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[tokio::test]
+    async fn signs_body_and_sends_once_on_success() {
+        let (url, requests) = start(StatusCode::OK).await;
+        let path = dead_letter_path("success");
+        let event = Event::new("main".to_string(), HashMap::new());
+
+        notifier(url, &path).notify(&event).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let (signature, body) = &requests[0];
+        assert_eq!(
+            signature.as_deref(),
+            Some(expected_signature(body).as_str())
+        );
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn retries_and_writes_dead_letter() {
+        let (url, requests) = start(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let path = dead_letter_path("retries");
+        let event = Event::new("main".to_string(), HashMap::new());
+
+        let error = notifier(url.clone(), &path)
+            .notify(&event)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            NotifyError::Status(StatusCode::INTERNAL_SERVER_ERROR)
+        ));
+
+        // Первая попытка и два повтора
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        let body = &requests[0].1;
+        assert!(requests.iter().all(|(_, b)| b == body));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(line["url"], url.as_str());
+        assert_eq!(line["body"], String::from_utf8_lossy(body).as_ref());
+        assert!(line["error"].as_str().unwrap().contains("500"));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let (url, requests) = start(StatusCode::BAD_REQUEST).await;
+        let path = dead_letter_path("client-error");
+        let event = Event::new(String::new(), HashMap::new());
+
+        assert!(notifier(url, &path).notify(&event).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn keeps_delivery_error_when_dead_letter_fails() {
+        let (url, _) = start(StatusCode::BAD_GATEWAY).await;
+        // Каталог вместо файла: запись невозможна
+        let path = std::env::temp_dir();
+        let event = Event::new(String::new(), HashMap::new());
+
+        let error = notifier(url, &path).notify(&event).await.unwrap_err();
+        assert!(matches!(
+            error,
+            NotifyError::Status(StatusCode::BAD_GATEWAY)
+        ));
+    }
+
+    #[tokio::test]
+    async fn renders_body_template() {
+        let (url, requests) = start(StatusCode::OK).await;
+        let path =
+            std::env::temp_dir().join(format!("wbsupplies-body-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"text": "{{#supplies}}{{warehouse}}: {{#costs}}{{short_date}} x{{coefficient}}{{/costs}}{{/supplies}}", "account": "{{account}}"}"#,
+        )
+        .unwrap();
+        let update = SupplyUpdateAcceptanceCosts {
+            supply: Supply {
+                warehouse_name: "Склад \"A\"".to_string(),
+                ..Default::default()
+            },
+            costs: vec![Cost {
+                date: "2026-10-20T00:00:00Z".to_string(),
+                coefficient: 1.0,
+                ..Default::default()
+            }],
+            closed_costs: Vec::new(),
+        };
+        let event = Event::new("main".to_string(), HashMap::from([(123, update)]));
+
+        WebhookNotifier::new(config::OutgoingWebhook {
+            url,
+            body_template: path.display().to_string(),
+            ..Default::default()
+        })
+        .notify(&event)
+        .await
+        .unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let requests = requests.lock().unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].1).unwrap();
+        assert_eq!(
+            body,
+            json!({ "text": "Склад \"A\": 2026-10-20 x1", "account": "main" })
+        );
+    }
+
+    #[tokio::test]
+    async fn waits_retry_after_instead_of_backoff() {
+        let (url, requests) = start_with(StatusCode::TOO_MANY_REQUESTS, Some("0")).await;
+        let notifier = WebhookNotifier::new(config::OutgoingWebhook {
+            url,
+            retries: 1,
+            backoff_millis: 60000,
+            ..Default::default()
+        });
+        let event = Event::new(String::new(), HashMap::new());
+
+        let result = tokio::time::timeout(Duration::from_secs(5), notifier.notify(&event)).await;
+        assert!(result.expect("Retry-After не учтён").is_err());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn caps_backoff() {
+        let (url, requests) = start(StatusCode::SERVICE_UNAVAILABLE).await;
+        let notifier = WebhookNotifier::new(config::OutgoingWebhook {
+            url,
+            retries: 2,
+            backoff_millis: 60000,
+            max_backoff_millis: 1,
+            ..Default::default()
+        });
+        let event = Event::new(String::new(), HashMap::new());
+
+        let result = tokio::time::timeout(Duration::from_secs(5), notifier.notify(&event)).await;
+        assert!(result.expect("задержка не ограничена").is_err());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,248 @@
+//! HTTP-сервер состояния: проверка работоспособности, текущие слоты,
+//! поток изменений (Server-Sent Events), метрики Prometheus и страница со слотами
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{
+        Html, IntoResponse, Response,
+        sse::{self, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures::Stream;
+use serde::Serialize;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use tokio::sync::broadcast;
+
+use crate::{
+    config::Config,
+    error,
+    i18n::msg,
+    metrics,
+    notify::{
+        self, Event, EventPayload, Notifier,
+        template::{Context, Template},
+    },
+    tracking::Trackers,
+    util,
+};
+
+/// Страница со слотами. Данные загружаются с `/supplies` и обновляются по `/events`
+const PAGE: &str = include_str!("page.html");
+
+/// Параметры HTTP-сервера
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Адрес, на котором слушает сервер
+    pub listen: SocketAddr,
+    /// Отдавать метрики Prometheus по адресу `/metrics`
+    pub metrics: bool,
+}
+
+struct ServerState {
+    trackers: Arc<Trackers>,
+    events: broadcast::Sender<String>,
+}
+
+/// Передача событий подписчикам `/events`
+#[derive(Clone)]
+pub struct EventStream {
+    tx: broadcast::Sender<String>,
+}
+
+#[async_trait]
+impl Notifier for EventStream {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn notify(&self, event: &Event) -> notify::error::Result<()> {
+        let data =
+            serde_json::to_string(&EventPayload::from(event)).map_err(std::io::Error::from)?;
+        // Ошибка означает, что подписчиков нет
+        let _ = self.tx.send(data);
+        Ok(())
+    }
+}
+
+/// Состояние отслеживания аккаунта
+#[derive(Serialize)]
+struct AccountHealth<'a> {
+    #[serde(skip_serializing_if = "str::is_empty")]
+    account: &'a str,
+    /// Время последнего успешного опроса
+    last_poll: Option<String>,
+    /// Сколько секунд назад получены учётные данные
+    credentials_age_secs: Option<i64>,
+    browser_alive: bool,
+    /// Неудачных опросов подряд
+    failures: u32,
+}
+
+/// Поставка с датами приёмки
+#[derive(Serialize)]
+struct SupplyDates {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    account: String,
+    preorder_id: i64,
+    supply_id: Option<i64>,
+    warehouse_id: i64,
+    warehouse: String,
+    url: String,
+    /// Даты по возрастанию. Недоступные отмечены отрицательным коэффициентом
+    dates: Vec<DatePayload>,
+}
+
+#[derive(Serialize)]
+struct DatePayload {
+    date: String,
+    coefficient: f64,
+    cost: f64,
+}
+
+/// Запуск сервера, если он включён.
+/// Возвращает получателя событий для потока `/events`
+pub fn start(cfg: &Config, trackers: &Arc<Trackers>) -> error::Result<Option<EventStream>> {
+    if !cfg.http_server.enabled {
+        return Ok(None);
+    }
+
+    let config = cfg.http_server.to_server_config()?;
+    let (tx, _) = broadcast::channel(64);
+    let state = Arc::new(ServerState {
+        trackers: Arc::clone(trackers),
+        events: tx.clone(),
+    });
+
+    tokio::spawn(async move {
+        if let Err(e) = serve(config, state).await {
+            tracing::error!(error = %e, "{}", msg().http_server_error);
+        }
+    });
+
+    Ok(Some(EventStream { tx }))
+}
+
+async fn serve(config: ServerConfig, state: Arc<ServerState>) -> std::io::Result<()> {
+    let mut app = Router::new()
+        .route("/", get(page))
+        .route("/health", get(health))
+        .route("/supplies", get(supplies))
+        .route("/events", get(events));
+    if config.metrics {
+        app = app.route("/metrics", get(metrics));
+    }
+    let app = app.with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(config.listen).await?;
+    tracing::info!(listen = %config.listen, "{}", msg().http_server_started);
+    axum::serve(listener, app).await
+}
+
+async fn page() -> Response {
+    let ctx = Context::default()
+        .with("title", msg().page_title)
+        .with("supply", msg().page_supply)
+        .with("warehouse", msg().page_warehouse)
+        .with("last_poll", msg().page_last_poll)
+        .with("no_supplies", msg().page_no_supplies);
+    match Template::parse(PAGE) {
+        Ok(template) => Html(template.html().render(&ctx)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// Состояние всех аккаунтов. Код 503, если хотя бы один аккаунт не опрашивается
+async fn health(State(state): State<Arc<ServerState>>) -> Response {
+    let now = chrono::Local::now();
+    let mut accounts = Vec::new();
+    let mut healthy = true;
+
+    for t in state.trackers.iter() {
+        let status = t.supplies.status().await;
+        let browser_alive = t.supplies.is_browser_alive();
+        healthy &= browser_alive && status.last_success.is_some() && !status.is_stalled();
+
+        accounts.push(AccountHealth {
+            account: &t.name,
+            last_poll: status.last_success.map(|t| t.to_rfc3339()),
+            credentials_age_secs: status.credentials_at.map(|t| (now - t).num_seconds()),
+            browser_alive,
+            failures: status.failures,
+        });
+    }
+
+    let code = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(accounts)).into_response()
+}
+
+/// Отслеживаемые поставки всех аккаунтов с датами приёмки
+async fn supplies(State(state): State<Arc<ServerState>>) -> Json<Vec<SupplyDates>> {
+    let mut result = Vec::new();
+
+    for t in state.trackers.iter() {
+        let supplies = t.supplies.read_supplies().await;
+        for (preorder_id, costs) in t.supplies.read_acceptance_costs().await {
+            let Some(supply) = supplies.get(&preorder_id) else {
+                continue;
+            };
+            let mut dates = costs
+                .into_values()
+                .map(|c| DatePayload {
+                    date: notify::short_date(&c.date).to_string(),
+                    coefficient: c.coefficient,
+                    cost: c.cost,
+                })
+                .collect::<Vec<_>>();
+            dates.sort_by(|a, b| a.date.cmp(&b.date));
+
+            result.push(SupplyDates {
+                account: t.name.clone(),
+                preorder_id,
+                supply_id: supply.supply_id,
+                warehouse_id: supply.warehouse_id,
+                warehouse: supply.warehouse_name.clone(),
+                url: util::preorder_id_to_url(preorder_id),
+                dates,
+            });
+        }
+    }
+
+    result.sort_by(|a, b| (&a.account, a.preorder_id).cmp(&(&b.account, b.preorder_id)));
+    Json(result)
+}
+
+/// Метрики в текстовом формате Prometheus
+async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::get().encode(),
+    )
+}
+
+/// Поток изменений: событие `update` с телом в формате исходящего webhook
+async fn events(
+    State(state): State<Arc<ServerState>>,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let rx = state.events.subscribe();
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(data) => {
+                    return Some((Ok(sse::Event::default().event("update").data(data)), rx));
+                }
+                // Пропущенные события не страшны: страница всё равно запрашивает снимок заново
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -1,23 +1,47 @@
-use reqwest::Client;
-use serde_json::json;
-use std::time::Duration;
+use reqwest::{
+    Client, RequestBuilder,
+    multipart::{Form, Part},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+use tokio::sync::{Mutex, mpsc::Sender};
 
-use crate::telebot::types;
+use crate::{i18n::msg, telebot::types};
 
 const BASE_URL: &str = "https://api.telegram.org";
 
+/// Максимальная длина подписи к фотографии
+pub const CAPTION_MAX_LEN: usize = 1024;
+
+/// Максимальная длина текста сообщения
+pub const MESSAGE_MAX_LEN: usize = 4096;
+
 pub struct Bot {
     token: String,
     client: Client,
     timeout: Duration,
-    chat_ids: Vec<String>,
+    /// Получатели и пользователи изменяются при перезагрузке конфигурации
+    targets: RwLock<Vec<types::ChatTarget>>,
+    allow_users: RwLock<Vec<i64>>,
     parse_mode: Option<String>,
+    /// Отправленные сообщения: (получатель, preorder_id) -> сообщение
+    messages: Mutex<HashMap<(types::ChatTarget, i64), SentMessage>>,
+}
+
+/// Отправленное сообщение о поставке
+#[derive(Debug, Clone, Copy)]
+struct SentMessage {
+    message_id: i64,
+    /// Сообщение с фотографией: текст хранится в подписи
+    is_photo: bool,
 }
 
 pub struct BotBuilder {
     token: String,
     timeout: Duration,
-    chat_ids: Vec<String>,
+    targets: Vec<types::ChatTarget>,
+    allow_users: Vec<i64>,
     parse_mode: Option<String>,
 }
 
@@ -27,7 +51,8 @@ impl BotBuilder {
         Self {
             token: token.into(),
             timeout: Duration::from_secs(5),
-            chat_ids: vec![],
+            targets: vec![],
+            allow_users: vec![],
             parse_mode: Some("HTML".to_string()),
         }
     }
@@ -42,13 +67,27 @@ impl BotBuilder {
         self
     }
 
-    pub fn add_chat_id(mut self, id: impl Into<String>) -> Self {
-        self.chat_ids.push(id.into());
+    pub fn add_chat_id(mut self, id: impl Into<types::ChatId>) -> Self {
+        self.targets.push(id.into().into());
         self
     }
 
     pub fn add_chat_ids<I: IntoIterator<Item = String>>(mut self, ids: I) -> Self {
-        self.chat_ids.extend(ids);
+        self.targets.extend(
+            ids.into_iter()
+                .map(|id| types::ChatId::from(id.as_str()).into()),
+        );
+        self
+    }
+
+    pub fn add_targets<I: IntoIterator<Item = types::ChatTarget>>(mut self, targets: I) -> Self {
+        self.targets.extend(targets);
+        self
+    }
+
+    /// Пользователи, которым разрешено управлять ботом
+    pub fn allow_users<I: IntoIterator<Item = i64>>(mut self, ids: I) -> Self {
+        self.allow_users.extend(ids);
         self
     }
 
@@ -57,60 +96,538 @@ impl BotBuilder {
             token: self.token,
             client: Client::new(),
             timeout: self.timeout,
-            chat_ids: self.chat_ids,
+            targets: RwLock::new(self.targets),
+            allow_users: RwLock::new(self.allow_users),
             parse_mode: self.parse_mode,
+            messages: Mutex::new(HashMap::new()),
         }
     }
 }
 
+#[allow(dead_code)]
 impl Bot {
+    /// Вызов метода Bot API
+    async fn call<T: DeserializeOwned>(&self, method: &str, payload: &Value) -> Result<T, String> {
+        self.call_with_timeout(method, payload, self.timeout).await
+    }
+
+    async fn call_with_timeout<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        payload: &Value,
+        timeout: Duration,
+    ) -> Result<T, String> {
+        let request = self.request(method).timeout(timeout).json(payload);
+        self.execute(request).await
+    }
+
+    /// Вызов метода Bot API с отправкой файлов
+    async fn call_multipart<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        form: Form,
+    ) -> Result<T, String> {
+        // Загрузка файлов занимает больше времени, чем обычный запрос
+        let request = self
+            .request(method)
+            .timeout(self.timeout * 4)
+            .multipart(form);
+        self.execute(request).await
+    }
+
+    fn request(&self, method: &str) -> RequestBuilder {
+        let url = format!("{}/bot{}/{}", BASE_URL, self.token, method);
+        self.client.post(url)
+    }
+
+    async fn execute<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        let response = request
+            .send()
+            .await
+            // Адрес запроса содержит токен бота
+            .map_err(|e| format!("{}: {}", msg().request_failed, e.without_url()))?;
+
+        let status = response.status();
+        let body = response
+            .json::<types::Response<T>>()
+            .await
+            .map_err(|_| format!("{} {status}", msg().status_error))?;
+
+        match body {
+            types::Response {
+                ok: true,
+                result: Some(result),
+                ..
+            } => Ok(result),
+            types::Response { description, .. } => Err(format!(
+                "{} {status}: {}",
+                msg().status_error,
+                description.unwrap_or_default()
+            )),
+        }
+    }
+
+    fn text_payload(
+        &self,
+        text: &str,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Value {
+        let mut payload = json!({ "text": text });
+
+        if let Some(mode) = &self.parse_mode {
+            payload["parse_mode"] = json!(mode);
+        }
+
+        if let Some(rm) = reply_markup {
+            payload["reply_markup"] = json!( {"inline_keyboard": rm} );
+        }
+
+        payload
+    }
+
+    /// Отправка сообщения. Возвращает message_id
     pub async fn send_message(
         &self,
-        chat_id: &str,
+        target: &types::ChatTarget,
+        text: &str,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Result<i64, String> {
+        let mut payload = self.text_payload(text, reply_markup);
+        payload["chat_id"] = json!(target.chat);
+
+        if let Some(thread_id) = target.thread_id {
+            payload["message_thread_id"] = json!(thread_id);
+        }
+
+        let message = self.call::<types::Message>("sendMessage", &payload).await?;
+
+        Ok(message.message_id)
+    }
+
+    /// Изменение текста ранее отправленного сообщения
+    pub async fn edit_message_text(
+        &self,
+        chat_id: &types::ChatId,
+        message_id: i64,
         text: &str,
         reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
     ) -> Result<(), String> {
-        let url = format!("{}/bot{}/sendMessage", BASE_URL, self.token);
-        let mut payload = json!({
-            "chat_id": chat_id,
-            "text": text
+        let mut payload = self.text_payload(text, reply_markup);
+        payload["chat_id"] = json!(chat_id);
+        payload["message_id"] = json!(message_id);
+
+        match self.call::<Value>("editMessageText", &payload).await {
+            // Текст не изменился — сообщение актуально
+            Err(e) if e.contains("message is not modified") => Ok(()),
+            r => r.map(|_| ()),
+        }
+    }
+
+    /// Текстовые поля формы: подпись, разметка и клавиатура
+    fn caption_form(
+        &self,
+        form: Form,
+        caption: &str,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Form {
+        let mut form = form.text("caption", caption.to_string());
+
+        if let Some(mode) = &self.parse_mode {
+            form = form.text("parse_mode", mode.clone());
+        }
+
+        if let Some(rm) = reply_markup {
+            form = form.text("reply_markup", json!({ "inline_keyboard": rm }).to_string());
+        }
+
+        form
+    }
+
+    fn photo_part(photo: Vec<u8>) -> Part {
+        Part::bytes(photo)
+            .file_name("screenshot.png")
+            .mime_str("image/png")
+            .unwrap_or_else(|e| panic!("{}: {e}", msg().invalid_mime))
+    }
+
+    /// Отправка фотографии с подписью. Возвращает message_id
+    pub async fn send_photo(
+        &self,
+        target: &types::ChatTarget,
+        photo: Vec<u8>,
+        caption: &str,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Result<i64, String> {
+        let mut form = Form::new()
+            .text("chat_id", target.chat.to_string())
+            .part("photo", Self::photo_part(photo));
+
+        if let Some(thread_id) = target.thread_id {
+            form = form.text("message_thread_id", thread_id.to_string());
+        }
+
+        let form = self.caption_form(form, caption, reply_markup);
+        let message = self
+            .call_multipart::<types::Message>("sendPhoto", form)
+            .await?;
+
+        Ok(message.message_id)
+    }
+
+    /// Замена фотографии и подписи ранее отправленного сообщения
+    pub async fn edit_message_photo(
+        &self,
+        chat_id: &types::ChatId,
+        message_id: i64,
+        photo: Vec<u8>,
+        caption: &str,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Result<(), String> {
+        let mut media = json!({
+            "type": "photo",
+            "media": "attach://photo",
+            "caption": caption
         });
 
         if let Some(mode) = &self.parse_mode {
-            payload["parse_mode"] = json!(mode);
+            media["parse_mode"] = json!(mode);
         }
 
+        let mut form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .text("message_id", message_id.to_string())
+            .text("media", media.to_string())
+            .part("photo", Self::photo_part(photo));
+
         if let Some(rm) = reply_markup {
-            payload["reply_markup"] = json!( {"inline_keyboard": rm} );
+            form = form.text("reply_markup", json!({ "inline_keyboard": rm }).to_string());
         }
 
-        let response = self
-            .client
-            .post(&url)
-            .timeout(self.timeout)
-            .json(&payload)
-            .send()
+        self.call_multipart::<Value>("editMessageMedia", form)
             .await
-            .map_err(|e| format!("Ошибка при запросе: {e}"))?;
+            .map(|_| ())
+    }
 
-        if !response.status().is_success() {
-            return Err(format!("Ошибка: статус {}", response.status()));
+    /// Изменение подписи ранее отправленной фотографии
+    pub async fn edit_message_caption(
+        &self,
+        chat_id: &types::ChatId,
+        message_id: i64,
+        caption: &str,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Result<(), String> {
+        let mut payload = self.text_payload(caption, reply_markup);
+        payload["caption"] = payload["text"].take();
+        payload["chat_id"] = json!(chat_id);
+        payload["message_id"] = json!(message_id);
+
+        match self.call::<Value>("editMessageCaption", &payload).await {
+            // Подпись не изменилась — сообщение актуально
+            Err(e) if e.contains("message is not modified") => Ok(()),
+            r => r.map(|_| ()),
         }
+    }
+
+    /// Удаление сообщения
+    pub async fn delete_message(
+        &self,
+        chat_id: &types::ChatId,
+        message_id: i64,
+    ) -> Result<(), String> {
+        let payload = json!({
+            "chat_id": chat_id,
+            "message_id": message_id
+        });
+
+        self.call::<bool>("deleteMessage", &payload).await?;
+
+        Ok(())
+    }
+
+    /// Получение входящих обновлений (long polling)
+    pub async fn get_updates(
+        &self,
+        offset: i64,
+        timeout_secs: u64,
+    ) -> Result<Vec<types::Update>, String> {
+        let payload = json!({
+            "offset": offset,
+            "timeout": timeout_secs,
+            "allowed_updates": ["callback_query"]
+        });
+
+        self.call_with_timeout(
+            "getUpdates",
+            &payload,
+            self.timeout + Duration::from_secs(timeout_secs),
+        )
+        .await
+    }
+
+    /// Получение обновлений в режиме long polling и передача их в канал
+    pub async fn run_polling(&self, tx: Sender<types::Update>) {
+        // getUpdates не работает, пока установлен webhook
+        let _ = self.delete_webhook().await;
+        let mut offset = 0;
+
+        loop {
+            let updates = match self.get_updates(offset, 30).await {
+                Ok(updates) => updates,
+                Err(_) => {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            for update in updates {
+                offset = update.update_id + 1;
+                if tx.send(update).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Установка webhook для получения обновлений
+    pub async fn set_webhook(&self, url: &str, secret_token: Option<&str>) -> Result<(), String> {
+        let mut payload = json!({
+            "url": url,
+            "allowed_updates": ["callback_query"]
+        });
+
+        if let Some(secret) = secret_token {
+            payload["secret_token"] = json!(secret);
+        }
+
+        self.call::<bool>("setWebhook", &payload).await?;
+
+        Ok(())
+    }
+
+    /// Удаление webhook
+    pub async fn delete_webhook(&self) -> Result<(), String> {
+        self.call::<bool>("deleteWebhook", &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Подтверждение нажатия на кнопку с обратным вызовом
+    pub async fn answer_callback_query(
+        &self,
+        callback_query_id: &str,
+        text: Option<&str>,
+    ) -> Result<(), String> {
+        let mut payload = json!({ "callback_query_id": callback_query_id });
+
+        if let Some(text) = text {
+            payload["text"] = json!(text);
+        }
+
+        self.call::<bool>("answerCallbackQuery", &payload).await?;
 
         Ok(())
     }
 
+    /// Проверка, что пользователю разрешено управлять ботом
+    pub fn is_allowed(&self, user_id: i64) -> bool {
+        self.allow_users.read().unwrap().contains(&user_id)
+    }
+
+    /// Замена пользователей, которым разрешено управлять ботом
+    pub fn set_allow_users(&self, ids: Vec<i64>) {
+        *self.allow_users.write().unwrap() = ids;
+    }
+
+    /// Получатели уведомлений
+    pub fn targets(&self) -> Vec<types::ChatTarget> {
+        self.targets.read().unwrap().clone()
+    }
+
+    /// Замена получателей уведомлений
+    pub fn set_targets(&self, targets: Vec<types::ChatTarget>) {
+        *self.targets.write().unwrap() = targets;
+    }
+
     pub async fn write(
         &self,
         message: impl Into<String>,
         reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
     ) -> Result<(), String> {
         let msg = message.into();
+        let mut errors = Vec::new();
+
+        for target in &self.targets() {
+            if let Err(e) = self.send_message(target, &msg, reply_markup).await {
+                log_send_error(target, &e);
+                errors.push(e);
+            }
+        }
+
+        join_errors(errors)
+    }
+
+    /// Отправка или изменение сообщения о поставке в чатах `targets`.
+    /// Если сообщение по поставке уже отправлялось — оно редактируется.
+    /// Ошибка в одном чате не мешает отправке в остальные
+    pub async fn write_supply(
+        &self,
+        targets: &[types::ChatTarget],
+        preorder_id: i64,
+        message: impl Into<String>,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Result<(), String> {
+        let msg = message.into();
+        let mut messages = self.messages.lock().await;
+        let mut errors = Vec::new();
+
+        for target in targets {
+            let key = (target.clone(), preorder_id);
+
+            if let Some(&sent) = messages.get(&key) {
+                let edited = if sent.is_photo {
+                    self.edit_message_caption(&target.chat, sent.message_id, &msg, reply_markup)
+                        .await
+                } else {
+                    self.edit_message_text(&target.chat, sent.message_id, &msg, reply_markup)
+                        .await
+                };
+                match edited {
+                    Ok(()) => continue,
+                    // Сообщение удалено пользователем — отправляем новое
+                    Err(e) if is_message_gone(&e) => {}
+                    Err(e) => {
+                        log_send_error(target, &e);
+                        errors.push(e);
+                        continue;
+                    }
+                }
+            }
 
-        for id in &self.chat_ids {
-            self.send_message(id, &msg, reply_markup.clone()).await?;
+            match self.send_message(target, &msg, reply_markup).await {
+                Ok(message_id) => {
+                    messages.insert(
+                        key,
+                        SentMessage {
+                            message_id,
+                            is_photo: false,
+                        },
+                    );
+                }
+                Err(e) => {
+                    log_send_error(target, &e);
+                    errors.push(e);
+                }
+            }
         }
 
+        join_errors(errors)
+    }
+
+    /// Отправка или изменение сообщения о поставке со скриншотом в чатах `targets`.
+    /// Текст сообщения передаётся в подписи к фотографии
+    pub async fn write_supply_photo(
+        &self,
+        targets: &[types::ChatTarget],
+        preorder_id: i64,
+        photo: Vec<u8>,
+        caption: impl Into<String>,
+        reply_markup: Option<&Vec<Vec<types::InlineKeyboardMarkup>>>,
+    ) -> Result<(), String> {
+        let caption = caption.into();
+        let mut messages = self.messages.lock().await;
+        let mut errors = Vec::new();
+
+        for target in targets {
+            let key = (target.clone(), preorder_id);
+
+            if let Some(&sent) = messages.get(&key) {
+                if sent.is_photo {
+                    let edited = self
+                        .edit_message_photo(
+                            &target.chat,
+                            sent.message_id,
+                            photo.clone(),
+                            &caption,
+                            reply_markup,
+                        )
+                        .await;
+                    match edited {
+                        Ok(()) => continue,
+                        Err(e) if is_message_gone(&e) => {}
+                        Err(e) => {
+                            log_send_error(target, &e);
+                            errors.push(e);
+                            continue;
+                        }
+                    }
+                }
+
+                // Текстовое сообщение нельзя превратить в фотографию — заменяем его.
+                // Старое сообщение удаляется, чтобы в чате не было двух сообщений о поставке
+                messages.remove(&key);
+                let _ = self.delete_message(&target.chat, sent.message_id).await;
+            }
+
+            match self
+                .send_photo(target, photo.clone(), &caption, reply_markup)
+                .await
+            {
+                Ok(message_id) => {
+                    messages.insert(
+                        key,
+                        SentMessage {
+                            message_id,
+                            is_photo: true,
+                        },
+                    );
+                }
+                Err(e) => {
+                    log_send_error(target, &e);
+                    errors.push(e);
+                }
+            }
+        }
+
+        join_errors(errors)
+    }
+
+    /// Удаление сообщений о поставке во всех чатах, куда они отправлялись
+    pub async fn delete_supply(&self, preorder_id: i64) -> Result<(), String> {
+        let mut messages = self.messages.lock().await;
+        let keys: Vec<_> = messages
+            .keys()
+            .filter(|(_, id)| *id == preorder_id)
+            .cloned()
+            .collect();
+        let mut errors = Vec::new();
+
+        for key in keys {
+            if let Some(sent) = messages.remove(&key)
+                && let Err(e) = self.delete_message(&key.0.chat, sent.message_id).await
+            {
+                tracing::warn!(chat = %key.0.chat, error = %e, "{}", msg().chat_delete_failed);
+                errors.push(e);
+            }
+        }
+
+        join_errors(errors)
+    }
+}
+
+/// Сообщение удалено или не существует: его можно только отправить заново
+fn is_message_gone(error: &str) -> bool {
+    error.contains("message to edit not found") || error.contains("MESSAGE_ID_INVALID")
+}
+
+fn log_send_error(target: &types::ChatTarget, error: &str) {
+    tracing::warn!(chat = %target.chat, error, "{}", msg().chat_send_failed);
+}
+
+/// Объединение ошибок отправки в несколько чатов
+fn join_errors(errors: Vec<String>) -> Result<(), String> {
+    if errors.is_empty() {
         Ok(())
+    } else {
+        Err(errors.join("; "))
     }
 }
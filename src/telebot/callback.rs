@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+/// Действие, закодированное в callback_data кнопки уведомления
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackAction {
+    /// Отключить уведомления по складу на 24 часа: (warehouse_id, номер аккаунта)
+    MuteWarehouse(i64, usize),
+    /// Отключить уведомления по поставке
+    MuteSupply(i64),
+    /// Показать все доступные даты поставки
+    ShowDates(i64),
+}
+
+impl CallbackAction {
+    /// Кодирование в callback_data (не более 64 байт)
+    pub fn encode(&self) -> String {
+        match self {
+            Self::MuteWarehouse(id, account) => format!("mw:{id}:{account}"),
+            Self::MuteSupply(id) => format!("ms:{id}"),
+            Self::ShowDates(id) => format!("sd:{id}"),
+        }
+    }
+}
+
+impl FromStr for CallbackAction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let kind = parts.next().ok_or(())?;
+        let id = parts.next().ok_or(())?.parse::<i64>().map_err(|_| ())?;
+        // Кнопки прежних версий не содержат номер аккаунта
+        let account = parts
+            .next()
+            .map(|a| a.parse::<usize>().map_err(|_| ()))
+            .transpose()?;
+        if parts.next().is_some() {
+            return Err(());
+        }
+
+        match (kind, account) {
+            ("mw", account) => Ok(Self::MuteWarehouse(id, account.unwrap_or_default())),
+            ("ms", None) => Ok(Self::MuteSupply(id)),
+            ("sd", None) => Ok(Self::ShowDates(id)),
+            _ => Err(()),
+        }
+    }
+}
@@ -1,4 +1,6 @@
 mod bot;
+pub mod callback;
 pub mod types;
+pub mod webhook;
 
 pub use bot::*;
@@ -3,5 +3,118 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InlineKeyboardMarkup {
     pub text: String,
-    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_data: Option<String>,
+}
+
+impl InlineKeyboardMarkup {
+    /// Кнопка-ссылка
+    pub fn url(text: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            url: Some(url.into()),
+            callback_data: None,
+        }
+    }
+
+    /// Кнопка с обратным вызовом
+    pub fn callback(text: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            url: None,
+            callback_data: Some(data.into()),
+        }
+    }
+}
+
+/// Идентификатор чата: числовой ID (пользователь, группа) или @username канала
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatId {
+    Id(i64),
+    Username(String),
+}
+
+impl From<&str> for ChatId {
+    fn from(value: &str) -> Self {
+        value
+            .parse::<i64>()
+            .map_or_else(|_| Self::Username(value.to_string()), Self::Id)
+    }
+}
+
+impl std::fmt::Display for ChatId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Username(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl From<i64> for ChatId {
+    fn from(value: i64) -> Self {
+        Self::Id(value)
+    }
+}
+
+/// Получатель сообщений: чат и, для форумов, тема
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChatTarget {
+    pub chat: ChatId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+}
+
+impl From<ChatId> for ChatTarget {
+    fn from(chat: ChatId) -> Self {
+        Self {
+            chat,
+            thread_id: None,
+        }
+    }
+}
+
+/// Ответ Bot API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response<T> {
+    pub ok: bool,
+    pub result: Option<T>,
+    pub description: Option<String>,
+}
+
+/// Отправленное сообщение
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub message_id: i64,
+    pub chat: Option<Chat>,
+    pub message_thread_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub id: i64,
+}
+
+/// Входящее обновление
+#[derive(Debug, Clone, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    pub callback_query: Option<CallbackQuery>,
+}
+
+/// Нажатие на кнопку с обратным вызовом
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallbackQuery {
+    pub id: String,
+    pub from: User,
+    pub message: Option<Message>,
+    pub data: Option<String>,
 }
@@ -0,0 +1,149 @@
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use axum_server::tls_rustls::RustlsConfig;
+use std::{net::SocketAddr, sync::Arc};
+use subtle::ConstantTimeEq;
+use tokio::sync::mpsc::Sender;
+
+use crate::telebot::types;
+
+/// Заголовок с секретным токеном, который Telegram передаёт в каждом запросе
+const SECRET_TOKEN_HEADER: &str = "x-telegram-bot-api-secret-token";
+
+/// Параметры сервера для приёма обновлений через webhook
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Адрес, на котором слушает сервер
+    pub listen: SocketAddr,
+    /// Путь, на который Telegram отправляет обновления
+    pub path: String,
+    /// Секретный токен для проверки заголовка
+    pub secret_token: String,
+    /// Пути к сертификату и ключу (PEM). Если не заданы — сервер работает по HTTP
+    pub tls: Option<(String, String)>,
+}
+
+struct WebhookState {
+    secret_token: String,
+    tx: Sender<types::Update>,
+}
+
+/// Запуск сервера. Принятые обновления передаются в канал
+pub async fn serve(config: WebhookConfig, tx: Sender<types::Update>) -> std::io::Result<()> {
+    let state = Arc::new(WebhookState {
+        secret_token: config.secret_token,
+        tx,
+    });
+    let app = Router::new()
+        .route(&config.path, post(handle_update))
+        .with_state(state);
+
+    match config.tls {
+        Some((cert, key)) => {
+            let tls = RustlsConfig::from_pem_file(cert, key).await?;
+            axum_server::bind_rustls(config.listen, tls)
+                .serve(app.into_make_service())
+                .await
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(config.listen).await?;
+            axum::serve(listener, app).await
+        }
+    }
+}
+
+async fn handle_update(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    // Сравнение за постоянное время, чтобы токен нельзя было подобрать по времени ответа
+    let received = headers
+        .get(SECRET_TOKEN_HEADER)
+        .map(|v| v.as_bytes())
+        .unwrap_or_default();
+    if !bool::from(received.ct_eq(state.secret_token.as_bytes())) {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let Ok(update) = serde_json::from_slice::<types::Update>(&body) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    if state.tx.send(update).await.is_err() {
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    const SECRET: &str = "test-secret";
+    const UPDATE: &str = r#"{"update_id": 42}"#;
+
+    /// Сервер на свободном порту. Возвращает адрес webhook и канал принятых обновлений
+    async fn start() -> (String, mpsc::Receiver<types::Update>) {
+        let listen = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .unwrap();
+        let (tx, rx) = mpsc::channel(4);
+        let config = WebhookConfig {
+            listen,
+            path: "/telegram".to_string(),
+            secret_token: SECRET.to_string(),
+            tls: None,
+        };
+        tokio::spawn(serve(config, tx));
+        (format!("http://{listen}/telegram"), rx)
+    }
+
+    /// POST-запрос с повтором, пока сервер не начал слушать порт
+    async fn post(url: &str, secret: Option<&str>, body: &str) -> u16 {
+        let client = reqwest::Client::new();
+        for _ in 0..50 {
+            let mut request = client.post(url).body(body.to_string());
+            if let Some(secret) = secret {
+                request = request.header(SECRET_TOKEN_HEADER, secret);
+            }
+            match request.send().await {
+                Ok(response) => return response.status().as_u16(),
+                Err(e) if e.is_connect() => tokio::time::sleep(Duration::from_millis(20)).await,
+                Err(e) => panic!("{e}"),
+            }
+        }
+        panic!("webhook server did not start");
+    }
+
+    #[tokio::test]
+    async fn accepts_update_with_secret() {
+        let (url, mut rx) = start().await;
+        assert_eq!(post(&url, Some(SECRET), UPDATE).await, 200);
+        assert_eq!(rx.recv().await.unwrap().update_id, 42);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_or_wrong_secret() {
+        let (url, mut rx) = start().await;
+        assert_eq!(post(&url, None, UPDATE).await, 401);
+        assert_eq!(post(&url, Some("wrong"), UPDATE).await, 401);
+        assert_eq!(post(&url, Some("test-secret-longer"), UPDATE).await, 401);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_update() {
+        let (url, mut rx) = start().await;
+        assert_eq!(post(&url, Some(SECRET), "not json").await, 400);
+        assert!(rx.try_recv().is_err());
+    }
+}
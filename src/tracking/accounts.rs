@@ -0,0 +1,126 @@
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::mpsc;
+
+use super::{SupplyUpdateAcceptanceCosts, TrackingOptions, TrackingSupplies};
+use crate::browser::BrowserSessionConfig;
+
+/// Обновления аккаунта: (название аккаунта, preorder_id -> обновление)
+pub type AccountUpdate = (String, HashMap<i64, SupplyUpdateAcceptanceCosts>);
+
+/// Отслеживание поставок аккаунта
+pub struct AccountTracker {
+    pub name: String,
+    pub supplies: Arc<TrackingSupplies>,
+}
+
+/// Отслеживание поставок всех аккаунтов в одном процессе
+pub struct Trackers(Vec<AccountTracker>);
+
+impl Trackers {
+    /// Запуск отслеживания для каждого аккаунта. Аккаунты работают независимо:
+    /// браузер и вход повторяются в фоне, не останавливая остальные
+    pub fn watch(
+        accounts: impl IntoIterator<Item = (String, BrowserSessionConfig, TrackingOptions)>,
+    ) -> Self {
+        let trackers = accounts
+            .into_iter()
+            .map(|(name, bs_config, options)| {
+                let span = tracing::info_span!("tracker", account = %name);
+                let supplies =
+                    span.in_scope(|| TrackingSupplies::watch(&name, &bs_config, options));
+                AccountTracker {
+                    name,
+                    supplies: Arc::new(supplies),
+                }
+            })
+            .collect();
+
+        Self(trackers)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AccountTracker> {
+        self.0.iter()
+    }
+
+    /// Отслеживание по названию аккаунта
+    pub fn get(&self, account: &str) -> Option<&Arc<TrackingSupplies>> {
+        self.0
+            .iter()
+            .find(|t| t.name == account)
+            .map(|t| &t.supplies)
+    }
+
+    /// Порядковый номер аккаунта (используется в кнопках уведомлений)
+    pub fn index_of(&self, account: &str) -> Option<usize> {
+        self.0.iter().position(|t| t.name == account)
+    }
+
+    pub fn get_index(&self, index: usize) -> Option<&Arc<TrackingSupplies>> {
+        self.0.get(index).map(|t| &t.supplies)
+    }
+
+    /// Отслеживание аккаунта, к которому относится поставка
+    pub async fn find_supply(&self, preorder_id: i64) -> Option<&Arc<TrackingSupplies>> {
+        for t in &self.0 {
+            if t.supplies
+                .read_acceptance_costs()
+                .await
+                .contains_key(&preorder_id)
+            {
+                return Some(&t.supplies);
+            }
+        }
+        None
+    }
+
+    /// Обновления всех аккаунтов для уведомлений: без отключённых складов и поставок
+    /// и с учётом фильтров аккаунта.
+    /// Канал закрывается, когда остановлено отслеживание всех аккаунтов
+    pub async fn subscribe(&self) -> mpsc::Receiver<AccountUpdate> {
+        self.subscribe_with(true).await
+    }
+
+    /// Все обновления всех аккаунтов без фильтров
+    pub async fn subscribe_all(&self) -> mpsc::Receiver<AccountUpdate> {
+        self.subscribe_with(false).await
+    }
+
+    async fn subscribe_with(&self, filtered: bool) -> mpsc::Receiver<AccountUpdate> {
+        let (tx, rx) = mpsc::channel(64);
+
+        for t in &self.0 {
+            let mut updates = t.supplies.subscribe_channel().await;
+            let supplies = Arc::clone(&t.supplies);
+            let name = t.name.clone();
+            let tx = tx.clone();
+
+            tokio::spawn(async move {
+                while updates.changed().await.is_ok() {
+                    let mut data = match &*updates.borrow_and_update() {
+                        Ok(Some(data)) => data.clone(),
+                        Ok(None) => continue,
+                        Err(()) => break,
+                    };
+                    if filtered {
+                        supplies.filter(&mut data).await;
+                        if data.is_empty() {
+                            continue;
+                        }
+                    }
+                    if tx.send((name.clone(), data)).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        rx
+    }
+
+    /// Остановка отслеживания всех аккаунтов
+    pub async fn close(&self) {
+        for t in &self.0 {
+            t.supplies.close().await;
+        }
+    }
+}
@@ -1,3 +1,5 @@
+mod accounts;
 mod supplies;
 
+pub use accounts::*;
 pub use supplies::*;
@@ -1,11 +1,16 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    panic::AssertUnwindSafe,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
+    time::{Duration, Instant},
 };
 
+use chrono::{DateTime, Local};
+use futures::FutureExt;
+use serde::Serialize;
 use tokio::{
     sync::{
         Mutex,
@@ -13,208 +18,704 @@ use tokio::{
     },
     task::JoinHandle,
 };
+use tracing::Instrument;
 
 use crate::{
-    browser::{BrowserSession, BrowserSessionConfig},
+    browser::{BrowserError, BrowserSession, BrowserSessionConfig, PageParams},
     error::Result,
+    i18n::msg,
+    metrics, util,
     wbseller::{
         Client,
+        error::WbSellerError,
         models::{Cost, Supply},
     },
 };
 
 /// Структура для хранения обновлений себестоимости поставок
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct SupplyUpdateAcceptanceCosts {
     pub supply: Supply,
+    /// Даты, ставшие доступными
     pub costs: Vec<Cost>,
+    /// Даты, снова ставшие недоступными
+    pub closed_costs: Vec<Cost>,
 }
 
 /// Карта принятой себестоимости: preorder_id -> (дата -> Cost)
 pub type AcceptanceCosts = HashMap<i64, HashMap<String, Cost>>;
 
+/// Отслеживаемые (не запланированные) поставки: preorder_id -> Supply
+pub type TrackedSupplies = HashMap<i64, Supply>;
+
+/// Через сколько неудачных опросов подряд выводится ошибка
+const STALLED_AFTER: u32 = 10;
+
 /// Тип сообщения об обновлении
 pub type UpdateMessage = std::result::Result<Option<HashMap<i64, SupplyUpdateAcceptanceCosts>>, ()>;
 
+/// Параметры опроса, изменяемые во время работы
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackingOptions {
+    /// За какой период (в днях) запрашивать себестоимость
+    pub days: u8,
+    /// Интервал обновления поставок
+    pub update_interval: Duration,
+    /// Интервал обновления учётных данных из браузера
+    pub refresh_credentials_interval: Duration,
+    /// Склады, по которым передаются обновления. Если пуст — все склады
+    pub warehouses: Vec<i64>,
+    /// Максимальный коэффициент приёмки появившихся дат
+    pub max_coefficient: Option<f64>,
+}
+
+impl Default for TrackingOptions {
+    fn default() -> Self {
+        Self {
+            days: 14,
+            update_interval: Duration::from_secs(5),
+            refresh_credentials_interval: Duration::from_secs(60 * 60),
+            warehouses: Vec::new(),
+            max_coefficient: None,
+        }
+    }
+}
+
+impl TrackingOptions {
+    /// Отбор обновлений по складам и коэффициенту
+    fn retain(&self, updates: &mut HashMap<i64, SupplyUpdateAcceptanceCosts>) {
+        updates.retain(|_, v| {
+            if !self.warehouses.is_empty() && !self.warehouses.contains(&v.supply.warehouse_id) {
+                return false;
+            }
+            if let Some(max) = self.max_coefficient {
+                v.costs.retain(|c| c.coefficient <= max);
+            }
+            !v.costs.is_empty() || !v.closed_costs.is_empty()
+        });
+    }
+}
+
+/// Фильтры обновлений, изменяемые во время работы
+#[derive(Debug, Default, Clone)]
+pub struct Filters {
+    /// Склады с отключёнными уведомлениями: warehouse_id -> момент окончания
+    pub muted_warehouses: HashMap<i64, Instant>,
+    /// Поставки с отключёнными уведомлениями
+    pub muted_supplies: HashSet<i64>,
+}
+
+impl Filters {
+    /// Проверка, отключены ли уведомления по поставке
+    pub fn is_muted(&self, supply: &Supply) -> bool {
+        if let Some(id) = supply.preorder_id
+            && self.muted_supplies.contains(&id)
+        {
+            return true;
+        }
+        self.muted_warehouses
+            .get(&supply.warehouse_id)
+            .is_some_and(|until| *until > Instant::now())
+    }
+
+    /// Удаление истёкших отключений складов и поставок, которых больше нет среди отслеживаемых
+    pub fn prune(&mut self, tracked: &TrackedSupplies) {
+        let now = Instant::now();
+        self.muted_warehouses.retain(|_, until| *until > now);
+        self.muted_supplies.retain(|id| tracked.contains_key(id));
+    }
+}
+
+/// Состояние опроса для проверки работоспособности
+#[derive(Debug, Clone, Default)]
+pub struct PollStatus {
+    /// Время последнего успешного опроса
+    pub last_success: Option<DateTime<Local>>,
+    /// Время получения учётных данных из браузера
+    pub credentials_at: Option<DateTime<Local>>,
+    /// Количество неудачных опросов подряд
+    pub failures: u32,
+}
+
+impl PollStatus {
+    /// Опросы не удаются слишком долго
+    pub fn is_stalled(&self) -> bool {
+        self.failures >= STALLED_AFTER
+    }
+}
+
 /// Основная структура для отслеживания поставок и себестоимости
 #[allow(dead_code)]
 pub struct TrackingSupplies {
-    browser_session: Arc<Mutex<BrowserSession>>,
+    browser_session: Arc<Mutex<Option<BrowserSession>>>,
     acceptance_costs: Arc<Mutex<AcceptanceCosts>>,
+    supplies: Arc<Mutex<TrackedSupplies>>,
+    filters: Arc<Mutex<Filters>>,
+    options: Arc<Mutex<TrackingOptions>>,
+    status: Arc<Mutex<PollStatus>>,
     channel: Sender<UpdateMessage>,
+    /// Держит канал открытым, пока нет подписчиков
+    _updates: Receiver<UpdateMessage>,
     background_handle: JoinHandle<()>,
     is_closed: AtomicBool,
 }
 
 #[allow(dead_code)]
 impl TrackingSupplies {
-    pub async fn watch(bs_config: &BrowserSessionConfig) -> Result<Self> {
-        let bs = Arc::new(Mutex::new(BrowserSession::launch(bs_config).await?));
-        let (tx, _) = tokio::sync::watch::channel(UpdateMessage::Ok(None));
-        let acceptance_costs = Arc::new(Mutex::new(HashMap::new()));
-
-        let background_handle = {
-            let bs = Arc::clone(&bs);
-            let acceptance_costs = Arc::clone(&acceptance_costs);
-            let tx = tx.clone();
-
-            // Интервалы обновления
-            let update_interval = std::time::Duration::from_secs(5);
-            let refresh_credentials_interval = std::time::Duration::from_secs(60 * 60);
-            let mut n: u32 = 0;
-
-            // Инициализация клиента
-            let mut client = {
-                let session = bs.lock().await;
-                Client::from_browser_session(&*session).await?
-            };
+    /// Запуск отслеживания. Браузер запускается и учётные данные получаются в фоновом процессе,
+    /// который повторяет попытки при неудаче, поэтому ошибка одного аккаунта не мешает остальным
+    pub fn watch(
+        account: &str,
+        bs_config: &BrowserSessionConfig,
+        options: TrackingOptions,
+    ) -> Self {
+        let (tx, rx) = tokio::sync::watch::channel(UpdateMessage::Ok(None));
+        let poller = Poller {
+            account: account.to_string(),
+            bs_config: bs_config.clone(),
+            browser_session: Arc::new(Mutex::new(None)),
+            acceptance_costs: Arc::new(Mutex::new(HashMap::new())),
+            supplies: Arc::new(Mutex::new(HashMap::new())),
+            filters: Arc::new(Mutex::new(Filters::default())),
+            options: Arc::new(Mutex::new(options)),
+            status: Arc::new(Mutex::new(PollStatus::default())),
+            channel: tx.clone(),
+        };
 
-            tokio::spawn(async move {
-                loop {
-                    // Обновление учётных данных по расписанию
-                    if update_interval * n > refresh_credentials_interval {
-                        let guard_bs = bs.lock().await;
-                        if let Ok(cli) = Client::from_browser_session(&*guard_bs).await {
-                            client = cli;
-                            n = 0;
-                        }
-                    }
-                    n += 1;
-
-                    // Получение поставок
-                    let supplies = match client.not_planned_list_supplies().await {
-                        Ok(response) => response.result.data,
-                        Err(_) => {
-                            tokio::time::sleep(update_interval).await;
-                            continue;
-                        }
-                    };
+        Self {
+            browser_session: Arc::clone(&poller.browser_session),
+            acceptance_costs: Arc::clone(&poller.acceptance_costs),
+            supplies: Arc::clone(&poller.supplies),
+            filters: Arc::clone(&poller.filters),
+            options: Arc::clone(&poller.options),
+            status: Arc::clone(&poller.status),
+            channel: tx,
+            _updates: rx,
+            background_handle: tokio::spawn(supervise(poller).in_current_span()),
+            is_closed: AtomicBool::new(false),
+        }
+    }
 
-                    if supplies.is_empty() {
-                        tokio::time::sleep(update_interval).await;
-                        continue;
-                    }
+    /// Подписка на канал обновлений. Обновления передаются без фильтров, см. [`Self::filter`]
+    pub async fn subscribe_channel(&self) -> Receiver<UpdateMessage> {
+        self.channel.subscribe()
+    }
 
-                    // Получение себестоимости по поставкам
-                    let data = match client.acceptance_costs_from_supplies(14, &supplies).await {
-                        Ok(d) => d,
-                        Err(_) => {
-                            tokio::time::sleep(update_interval).await;
-                            continue;
-                        }
-                    };
+    /// Исключение поставок с отключёнными уведомлениями и не подходящих под параметры аккаунта
+    pub async fn filter(&self, updates: &mut HashMap<i64, SupplyUpdateAcceptanceCosts>) {
+        {
+            let filters = self.filters.lock().await;
+            updates.retain(|_, v| !filters.is_muted(&v.supply));
+        }
+        self.options.lock().await.retain(updates);
+    }
 
-                    if data.is_empty() {
-                        tokio::time::sleep(update_interval).await;
-                        continue;
-                    }
+    /// Получение текущей карты себестоимостей
+    pub async fn read_acceptance_costs(&self) -> AcceptanceCosts {
+        self.acceptance_costs.lock().await.clone()
+    }
 
-                    // Карта поставок по preorder_id
-                    let supplies_map: HashMap<_, _> = supplies
-                        .into_iter()
-                        .filter_map(|s| s.preorder_id.map(|id| (id, s)))
-                        .collect();
+    /// Получение текущего списка отслеживаемых поставок
+    pub async fn read_supplies(&self) -> TrackedSupplies {
+        self.supplies.lock().await.clone()
+    }
 
-                    let mut updated_acceptance_costs = HashMap::new();
-                    let mut guard = acceptance_costs.lock().await;
+    /// Состояние опроса
+    pub async fn status(&self) -> PollStatus {
+        self.status.lock().await.clone()
+    }
 
-                    // Удаление устаревших записей
-                    let obsolete_keys: Vec<_> = guard
-                        .keys()
-                        .filter(|k| !supplies_map.contains_key(k))
-                        .cloned()
-                        .collect();
+    /// Браузер работает. Занятая сессия (например, при обновлении учётных данных) считается рабочей
+    pub fn is_browser_alive(&self) -> bool {
+        self.browser_session
+            .try_lock()
+            .map_or(true, |bs| bs.as_ref().is_some_and(BrowserSession::is_alive))
+    }
 
-                    for key in obsolete_keys {
-                        guard.remove(&key);
-                    }
+    /// Изменение параметров опроса. Применяется со следующей итерации
+    pub async fn set_options(&self, options: TrackingOptions) {
+        *self.options.lock().await = options;
+    }
 
-                    for (k, v) in data {
-                        let new_costs_map = v
-                            .into_iter()
-                            .map(|c| (c.date.clone(), c))
-                            .collect::<HashMap<_, _>>();
+    /// Отключение уведомлений по складу на заданное время
+    pub async fn mute_warehouse(&self, warehouse_id: i64, duration: Duration) {
+        self.filters
+            .lock()
+            .await
+            .muted_warehouses
+            .insert(warehouse_id, Instant::now() + duration);
+    }
 
-                        if !guard.contains_key(&k) {
-                            guard.insert(k, new_costs_map);
-                            continue;
-                        }
+    /// Отключение уведомлений по поставке
+    pub async fn mute_supply(&self, preorder_id: i64) {
+        self.filters.lock().await.muted_supplies.insert(preorder_id);
+    }
 
-                        // Обновление и выявление изменений в себестоимости
-                        if let Some(old_costs) = guard.get(&k) {
-                            for (date, old_cost) in old_costs {
-                                if let Some(new_cost) = new_costs_map.get(date) {
-                                    // Считаем, что обновление произошло, если коэффициент стал неотрицательным
-                                    if old_cost.coefficient < 0. && new_cost.coefficient >= 0. {
-                                        updated_acceptance_costs
-                                            .entry(k)
-                                            .or_insert_with(|| SupplyUpdateAcceptanceCosts {
-                                                supply: supplies_map[&k].clone(),
-                                                costs: Vec::new(),
-                                            })
-                                            .costs
-                                            .push(new_cost.clone());
-                                    }
-                                }
-                            }
-                        }
+    /// Скриншот страницы поставки в авторизованной сессии браузера
+    pub async fn screenshot_supply(
+        &self,
+        preorder_id: i64,
+        selector: Option<&str>,
+    ) -> Result<Vec<u8>> {
+        let params = PageParams {
+            wait_for_navigation: Some(2000),
+            wait_open_on_page: Some(3000),
+            wait_for_el: Some(("#root", 5000)),
+            duration: 1500,
+            ..Default::default()
+        };
+        let url = util::preorder_id_to_url(preorder_id);
+        let session = self.browser_session.lock().await;
+        let session = session.as_ref().ok_or(BrowserError::BrowserLaunch)?;
 
-                        guard.insert(k, new_costs_map);
-                    }
+        Ok(session.screenshot(&url, &params, selector).await?)
+    }
 
-                    drop(guard);
+    /// Закрытие и остановка фонового процесса
+    pub async fn close(&self) {
+        // Защита от повторного вызова
+        if self
+            .is_closed
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
 
-                    if tx.is_closed() {
-                        break;
-                    }
+        // Опрос останавливается первым, чтобы не перезапустить закрываемый браузер
+        self.background_handle.abort();
+        if let Some(session) = self.browser_session.lock().await.as_mut() {
+            session.close().await;
+        }
+        let _ = self.channel.send(Err(()));
+    }
+}
+
+/// Состояние фонового опроса, общее с [`TrackingSupplies`]
+#[derive(Clone)]
+struct Poller {
+    account: String,
+    bs_config: BrowserSessionConfig,
+    browser_session: Arc<Mutex<Option<BrowserSession>>>,
+    acceptance_costs: Arc<Mutex<AcceptanceCosts>>,
+    supplies: Arc<Mutex<TrackedSupplies>>,
+    filters: Arc<Mutex<Filters>>,
+    options: Arc<Mutex<TrackingOptions>>,
+    status: Arc<Mutex<PollStatus>>,
+    channel: Sender<UpdateMessage>,
+}
 
-                    // Отправка обновлений
-                    if !updated_acceptance_costs.is_empty() {
-                        if tx.send(Ok(Some(updated_acceptance_costs))).is_err() {
-                            break;
+/// Перезапуск опроса после паники. Опрос завершается только остановкой отслеживания
+async fn supervise(poller: Poller) {
+    loop {
+        if AssertUnwindSafe(poll(poller.clone()))
+            .catch_unwind()
+            .await
+            .is_ok()
+        {
+            break;
+        }
+        tracing::error!("{}", msg().tracking_restarted);
+        let update_interval = poller.options.lock().await.update_interval;
+        tokio::time::sleep(update_interval).await;
+    }
+    let _ = poller.channel.send(Err(()));
+}
+
+/// Цикл опроса поставок и себестоимости
+async fn poll(poller: Poller) {
+    let Poller {
+        account,
+        bs_config,
+        browser_session: bs,
+        acceptance_costs,
+        supplies: tracked_supplies,
+        filters,
+        options,
+        status,
+        channel: tx,
+    } = poller;
+    let mut client = None;
+    let mut credentials_refreshed_at = Instant::now();
+    let metrics = metrics::get();
+    // Склады, по которым выставлены показатели слотов
+    let mut warehouses = HashSet::new();
+
+    for cycle in 1u64.. {
+        // Параметры читаются на каждой итерации, чтобы применять изменения конфигурации
+        let current = options.lock().await.clone();
+        let TrackingOptions {
+            days,
+            update_interval,
+            refresh_credentials_interval,
+            ..
+        } = current;
+
+        let started = Instant::now();
+        let polled = async {
+            let mut guard_bs = bs.lock().await;
+
+            // Запуск браузера, в том числе после потери соединения.
+            // Учётные данные нового браузера получаются сразу
+            let mut restarted = false;
+            if !guard_bs.as_ref().is_some_and(BrowserSession::is_alive) {
+                let relaunch = match guard_bs.take() {
+                    Some(mut session) => {
+                        session.close().await;
+                        true
+                    }
+                    None => false,
+                };
+                match BrowserSession::launch(&bs_config).await {
+                    Ok(session) => {
+                        *guard_bs = Some(session);
+                        restarted = true;
+                        if relaunch {
+                            metrics
+                                .browser_restarts
+                                .with_label_values(&[&account])
+                                .inc();
+                            tracing::warn!("{}", msg().browser_restarted);
+                        }
+                    }
+                    Err(e) => tracing::warn!(
+                        error = %e,
+                        "{}",
+                        if relaunch {
+                            msg().browser_restart_failed
+                        } else {
+                            msg().browser_launch_failed
                         }
-                    } else {
-                        let _ = tx.send(Ok(None));
+                    ),
+                }
+            }
+
+            // Получение учётных данных, пока их нет, и обновление по расписанию
+            if let Some(session) = guard_bs.as_ref()
+                && (restarted
+                    || client.is_none()
+                    || credentials_refreshed_at.elapsed() > refresh_credentials_interval)
+            {
+                metrics
+                    .credential_refreshes
+                    .with_label_values(&[&account])
+                    .inc();
+                match Client::from_browser_session(session).await {
+                    Ok(cli) => {
+                        client = Some(cli);
+                        credentials_refreshed_at = Instant::now();
+                        status.lock().await.credentials_at = Some(Local::now());
+                        tracing::info!("{}", msg().credentials_refreshed);
+                    }
+                    Err(e) => {
+                        metrics
+                            .credential_refresh_failures
+                            .with_label_values(&[&account])
+                            .inc();
+                        tracing::warn!(
+                            error = %e,
+                            "{}",
+                            msg().credentials_refresh_failed
+                        )
                     }
+                }
+            }
+            drop(guard_bs);
+
+            // Без учётных данных опрос невозможен, попытка повторится на следующей итерации
+            let Some(client) = &client else {
+                return Err(WbSellerError::Custom(msg().login_failed.to_string()));
+            };
 
-                    tokio::time::sleep(update_interval).await;
+            // Получение поставок
+            let supplies = client
+                .not_planned_list_supplies()
+                .await
+                .inspect_err(|e| tracing::warn!(error = %e, "{}", msg().supplies_request_failed))?
+                .result
+                .data;
+
+            // Карта поставок по preorder_id
+            let supplies_map: HashMap<_, _> = supplies
+                .iter()
+                .filter_map(|s| s.preorder_id.map(|id| (id, s.clone())))
+                .collect();
+            *tracked_supplies.lock().await = supplies_map.clone();
+            // Истёкшие отключения и отключения ушедших поставок больше не нужны
+            filters.lock().await.prune(&supplies_map);
+
+            // Без поставок или дат прежние данные устарели: последняя поставка могла быть запланирована
+            if supplies.is_empty() {
+                tracing::debug!(supplies = 0, "poll");
+                acceptance_costs.lock().await.clear();
+                return Ok(HashMap::new());
+            }
+
+            // Получение себестоимости по поставкам
+            let data = client
+                .acceptance_costs_from_supplies(days, &supplies)
+                .await?;
+
+            if data.is_empty() {
+                acceptance_costs.lock().await.clear();
+                return Ok(HashMap::new());
+            }
+
+            let mut updated_acceptance_costs = HashMap::new();
+            let mut guard = acceptance_costs.lock().await;
+
+            // Удаление устаревших записей
+            let obsolete_keys: Vec<_> = guard
+                .keys()
+                .filter(|k| !supplies_map.contains_key(k))
+                .cloned()
+                .collect();
+
+            for key in obsolete_keys {
+                guard.remove(&key);
+            }
+
+            for (k, v) in data {
+                let new_costs_map = v
+                    .into_iter()
+                    .map(|c| (c.date.clone(), c))
+                    .collect::<HashMap<_, _>>();
+
+                if !guard.contains_key(&k) {
+                    guard.insert(k, new_costs_map);
+                    continue;
                 }
 
-                let _ = tx.send(Err(()));
-            })
-        };
+                // Обновление и выявление изменений в себестоимости
+                if let Some(old_costs) = guard.get(&k) {
+                    for (date, old_cost) in old_costs {
+                        if let Some(new_cost) = new_costs_map.get(date) {
+                            // Считаем, что обновление произошло, если коэффициент стал неотрицательным
+                            let opened = old_cost.coefficient < 0. && new_cost.coefficient >= 0.;
+                            // И наоборот: дата закрылась, если коэффициент снова стал отрицательным
+                            let closed = old_cost.coefficient >= 0. && new_cost.coefficient < 0.;
+                            if !opened && !closed {
+                                continue;
+                            }
 
-        Ok(Self {
-            browser_session: bs,
-            acceptance_costs: acceptance_costs,
-            channel: tx,
-            background_handle: background_handle,
-            is_closed: AtomicBool::new(false),
-        })
+                            let update = updated_acceptance_costs.entry(k).or_insert_with(|| {
+                                SupplyUpdateAcceptanceCosts {
+                                    supply: supplies_map[&k].clone(),
+                                    ..Default::default()
+                                }
+                            });
+                            if opened {
+                                update.costs.push(new_cost.clone());
+                            } else {
+                                update.closed_costs.push(new_cost.clone());
+                            }
+                        }
+                    }
+                }
+
+                guard.insert(k, new_costs_map);
+            }
+
+            drop(guard);
+
+            tracing::debug!(
+                supplies = supplies_map.len(),
+                updates = updated_acceptance_costs.len(),
+                "poll"
+            );
+            Ok::<_, WbSellerError>(updated_acceptance_costs)
+        }
+        .instrument(tracing::info_span!("poll", cycle))
+        .await;
+
+        metrics.polls.with_label_values(&[&account]).inc();
+        metrics
+            .poll_duration
+            .with_label_values(&[&account])
+            .observe(started.elapsed().as_secs_f64());
+
+        match polled {
+            Ok(updated_acceptance_costs) => {
+                let failures = {
+                    let mut status = status.lock().await;
+                    status.last_success = Some(Local::now());
+                    std::mem::take(&mut status.failures)
+                };
+                if failures >= STALLED_AFTER {
+                    tracing::info!(failures, "{}", msg().tracking_recovered);
+                }
+                // Без поставок показатели складов удаляются, а число поставок обнуляется
+                record_slots(
+                    &account,
+                    &*tracked_supplies.lock().await,
+                    &*acceptance_costs.lock().await,
+                    &mut warehouses,
+                );
+
+                // Отправка обновлений
+                let _ = tx.send(Ok(
+                    (!updated_acceptance_costs.is_empty()).then_some(updated_acceptance_costs)
+                ));
+            }
+            Err(_) => {
+                metrics.poll_failures.with_label_values(&[&account]).inc();
+                let failures = {
+                    let mut status = status.lock().await;
+                    status.failures += 1;
+                    status.failures
+                };
+                if failures == STALLED_AFTER {
+                    tracing::error!(failures, "{}", msg().tracking_stalled);
+                }
+            }
+        }
+
+        tokio::time::sleep(update_interval).await;
     }
+}
 
-    /// Подписка на канал обновлений
-    pub async fn subscribe_channel(&self) -> Receiver<UpdateMessage> {
-        self.channel.subscribe()
+#[derive(Default)]
+struct WarehouseSlots<'a> {
+    dates: HashSet<&'a str>,
+    min: Option<f64>,
+}
+
+/// Показатели слотов аккаунта: число поставок, доступные даты и минимальный коэффициент по складам.
+/// `warehouses` — склады с выставленными показателями, у исчезнувших складов показатели удаляются
+fn record_slots(
+    account: &str,
+    supplies: &TrackedSupplies,
+    costs: &AcceptanceCosts,
+    warehouses: &mut HashSet<(i64, String)>,
+) {
+    let metrics = metrics::get();
+    metrics
+        .tracked_supplies
+        .with_label_values(&[account])
+        .set(supplies.len() as i64);
+
+    // (ID, название склада) -> доступные даты и минимальный коэффициент
+    let mut slots: HashMap<(i64, String), WarehouseSlots> = HashMap::new();
+    for (preorder_id, supply) in supplies {
+        let WarehouseSlots { dates, min } = slots
+            .entry((supply.warehouse_id, supply.warehouse_name.clone()))
+            .or_default();
+        let available = costs
+            .get(preorder_id)
+            .into_iter()
+            .flat_map(|c| c.values())
+            .filter(|c| c.coefficient >= 0.);
+        for c in available {
+            dates.insert(&c.date);
+            *min = Some(min.map_or(c.coefficient, |m| m.min(c.coefficient)));
+        }
     }
 
-    /// Получение текущей карты себестоимостей
-    pub async fn read_acceptance_costs(&self) -> AcceptanceCosts {
-        self.acceptance_costs.lock().await.clone()
+    for (id, name) in warehouses.iter().filter(|w| !slots.contains_key(*w)) {
+        let labels = [account, &id.to_string(), name];
+        let _ = metrics.open_slots.remove_label_values(&labels);
+        let _ = metrics.min_coefficient.remove_label_values(&labels);
     }
+    for ((id, name), WarehouseSlots { dates, min }) in &slots {
+        let labels = [account, &id.to_string(), name];
+        metrics
+            .open_slots
+            .with_label_values(&labels)
+            .set(dates.len() as i64);
+        match min {
+            Some(min) => metrics.min_coefficient.with_label_values(&labels).set(*min),
+            None => {
+                let _ = metrics.min_coefficient.remove_label_values(&labels);
+            }
+        }
+    }
+    *warehouses = slots.into_keys().collect();
+}
 
-    /// Закрытие и остановка фонового процесса
-    pub async fn close(&self) {
-        // Защита от повторного вызова
-        if self
-            .is_closed
-            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
-            .is_err()
-        {
-            return;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(date: &str, coefficient: f64) -> Cost {
+        Cost {
+            date: date.to_string(),
+            coefficient,
+            ..Default::default()
         }
+    }
 
-        self.browser_session.lock().await.close().await;
-        self.background_handle.abort();
+    /// Строки показателей аккаунта в формате Prometheus
+    fn series(account: &str) -> Vec<String> {
+        metrics::get()
+            .encode()
+            .lines()
+            .filter(|l| l.contains(&format!("account=\"{account}\"")))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn prunes_expired_and_untracked_mutes() {
+        let now = Instant::now();
+        let mut filters = Filters {
+            muted_warehouses: HashMap::from([
+                (507, now - Duration::from_secs(1)),
+                (117986, now + Duration::from_secs(60)),
+            ]),
+            muted_supplies: HashSet::from([1, 2]),
+        };
+
+        filters.prune(&TrackedSupplies::from([(2, Supply::default())]));
+
+        assert_eq!(
+            filters.muted_warehouses.keys().collect::<Vec<_>>(),
+            [&117986]
+        );
+        assert_eq!(filters.muted_supplies, HashSet::from([2]));
+    }
+
+    #[test]
+    fn removes_slot_series_when_supplies_are_gone() {
+        let account = "test-record-slots";
+        let supplies = TrackedSupplies::from([(
+            1,
+            Supply {
+                preorder_id: Some(1),
+                warehouse_id: 507,
+                warehouse_name: "Коледино".to_string(),
+                ..Default::default()
+            },
+        )]);
+        let costs = AcceptanceCosts::from([(
+            1,
+            HashMap::from([
+                ("2026-10-20".to_string(), cost("2026-10-20", 1.0)),
+                ("2026-10-21".to_string(), cost("2026-10-21", -1.0)),
+            ]),
+        )]);
+        let mut warehouses = HashSet::new();
+
+        record_slots(account, &supplies, &costs, &mut warehouses);
+        let recorded = series(account);
+        assert!(
+            recorded
+                .iter()
+                .any(|l| l.starts_with("wbsupplies_open_slots{") && l.ends_with(" 1"))
+        );
+        assert!(
+            recorded
+                .iter()
+                .any(|l| l.starts_with("wbsupplies_min_coefficient{"))
+        );
+
+        record_slots(account, &HashMap::new(), &HashMap::new(), &mut warehouses);
+        assert_eq!(
+            series(account)
+                .iter()
+                .map(|l| l.split('{').next().unwrap())
+                .collect::<Vec<_>>(),
+            ["wbsupplies_tracked_supplies"]
+        );
+        assert!(warehouses.is_empty());
     }
 }
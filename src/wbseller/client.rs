@@ -8,6 +8,7 @@ use std::collections::HashMap;
 
 use crate::{
     browser::{BrowserSession, PageParams},
+    i18n::msg,
     wbseller::models::{AcceptanceCostsResponse, Cost, ListSuppliesResponse, Supply},
 };
 
@@ -92,7 +93,12 @@ impl Client {
 
     /// Отправка HTTP-запроса и десериализация ответа
     async fn send_request<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T> {
-        let response = builder.headers(self.headers.clone()).send().await?;
+        // Ошибки авторизации и сервера не должны выглядеть как пустой ответ
+        let response = builder
+            .headers(self.headers.clone())
+            .send()
+            .await?
+            .error_for_status()?;
 
         let parsed = response.json::<T>().await?;
 
@@ -175,7 +181,10 @@ impl Client {
                 let response = self
                     .get_acceptance_costs(preorder_id, days)
                     .await
-                    .unwrap_or_else(|_| AcceptanceCostsResponse::default());
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(preorder_id, error = %e, "{}", msg().costs_request_failed);
+                        AcceptanceCostsResponse::default()
+                    });
 
                 result.insert(preorder_id, response.result.costs);
             }